@@ -0,0 +1,38 @@
+/* Copyright (C) 2022 Casper Meijn <casper@meijn.net>
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use advent_of_code_2022::{bench, input::InputDir, DAYS};
+
+const ITERATIONS: usize = 10;
+
+fn main() {
+    let input_dir = InputDir::from_env();
+    println!("{}", bench::DayBenchmark::HEADER);
+    for day in DAYS {
+        let text = match input_dir.load(day.day()) {
+            Ok(text) => text,
+            Err(error) => {
+                eprintln!("skipping: {error}");
+                continue;
+            }
+        };
+        match bench::bench(day, &text, ITERATIONS) {
+            Ok(benchmark) => println!("{benchmark}"),
+            Err(error) => eprintln!("skipping: day {}: {error}", day.day()),
+        }
+    }
+}
@@ -0,0 +1,224 @@
+/* Copyright (C) 2022 Casper Meijn <casper@meijn.net>
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use crate::{
+    parse::{lines, ParseError},
+    runner::PuzzleResult,
+};
+use std::{collections::BTreeMap, fmt};
+
+/// File name of the stored answers, inside the input directory.
+pub const ANSWERS_FILE: &str = "answers.txt";
+
+/// Known correct answers, stored one per line as `<day> <part> <answer>`.
+///
+/// Newlines and backslashes in an answer are escaped as `\n` and `\\`. Empty lines and lines
+/// starting with `#` are ignored.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Answers {
+    answers: BTreeMap<(u8, u8), String>,
+}
+
+pub fn parse(text: &str) -> Result<Answers, ParseError> {
+    let mut answers = Answers::default();
+    for line in lines(text) {
+        if line.text().is_empty() || line.text().starts_with('#') {
+            continue;
+        }
+        let (day, rest) = line.split_once(line.text(), ' ')?;
+        let (part, answer) = line.split_once(rest, ' ')?;
+        let day = line.parse(day, "a day")?;
+        let part = line.parse(part, "a part")?;
+        answers.answers.insert((day, part), unescape(answer));
+    }
+    Ok(answers)
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('\n', "\\n")
+}
+
+fn unescape(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        match (c, chars.clone().next()) {
+            ('\\', Some('n')) => {
+                result.push('\n');
+                chars.next();
+            }
+            ('\\', Some('\\')) => {
+                result.push('\\');
+                chars.next();
+            }
+            _ => result.push(c),
+        }
+    }
+    result
+}
+
+impl Answers {
+    pub fn get(&self, day: u8, part: u8) -> Option<&str> {
+        self.answers.get(&(day, part)).map(String::as_str)
+    }
+
+    pub fn insert(&mut self, day: u8, part: u8, answer: impl Into<String>) {
+        self.answers.insert((day, part), answer.into());
+    }
+
+    pub fn verify(&self, result: &PuzzleResult) -> Verdict {
+        match self.get(result.day, result.part) {
+            None => Verdict::Missing,
+            Some(expected) if expected == result.answer.to_string() => Verdict::Pass,
+            Some(expected) => Verdict::Fail {
+                expected: expected.to_string(),
+            },
+        }
+    }
+}
+
+impl fmt::Display for Answers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for ((day, part), answer) in &self.answers {
+            writeln!(f, "{day} {part} {}", escape(answer))?;
+        }
+        Ok(())
+    }
+}
+
+/// Outcome of comparing a computed answer with the stored one.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Verdict {
+    Pass,
+    Fail { expected: String },
+    Missing,
+}
+
+/// Formats a verification table with one row per result.
+pub fn format_table(answers: &Answers, results: &[PuzzleResult]) -> String {
+    let mut table = String::from("Day  Part  Result   Answer\n");
+    for result in results {
+        let answer = escape(&result.answer.to_string());
+        let row = match answers.verify(result) {
+            Verdict::Pass => format!("pass     {answer}"),
+            Verdict::Fail { expected } => {
+                format!("FAIL     {answer} (expected {})", escape(&expected))
+            }
+            Verdict::Missing => format!("missing  {answer}"),
+        };
+        table.push_str(&format!("{:>3}  {:>4}  {row}\n", result.day, result.part));
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{input::InputDir, runner, solution::Answer, DAYS};
+
+    const EXAMPLE_TEXT: &str = "\
+# day part answer
+1 1 24000
+5 1 CMZ
+10 2 ##..\\n..##\\n
+";
+
+    fn result(day: u8, part: u8, answer: impl Into<Answer>) -> PuzzleResult {
+        PuzzleResult {
+            day,
+            part,
+            answer: answer.into(),
+            duration: Default::default(),
+            day_duration: Default::default(),
+        }
+    }
+
+    #[test]
+    fn parse_example() {
+        let answers = parse(EXAMPLE_TEXT).unwrap();
+        assert_eq!(answers.get(1, 1), Some("24000"));
+        assert_eq!(answers.get(5, 1), Some("CMZ"));
+        assert_eq!(answers.get(10, 2), Some("##..\n..##\n"));
+        assert_eq!(answers.get(1, 2), None);
+        assert_eq!(
+            answers.to_string(),
+            EXAMPLE_TEXT.replacen("# day part answer\n", "", 1)
+        );
+    }
+
+    #[test]
+    fn parse_invalid() {
+        let error = parse("1 1 24000\nx 2 45000").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 2, column 1: expected a day, found `x`"
+        );
+    }
+
+    #[test]
+    fn escape_roundtrip() {
+        for text in ["", "a\\nb", "a\nb\\", "\\\\n"] {
+            assert_eq!(unescape(&escape(text)), text);
+        }
+    }
+
+    #[test]
+    fn verify() {
+        let answers = parse(EXAMPLE_TEXT).unwrap();
+        assert_eq!(answers.verify(&result(1, 1, 24000_u32)), Verdict::Pass);
+        assert_eq!(
+            answers.verify(&result(5, 1, "MCD".to_string())),
+            Verdict::Fail {
+                expected: "CMZ".to_string()
+            }
+        );
+        assert_eq!(answers.verify(&result(1, 2, 45000_u32)), Verdict::Missing);
+    }
+
+    #[test]
+    fn format_table_example() {
+        let answers = parse(EXAMPLE_TEXT).unwrap();
+        let results = [
+            result(1, 1, 24000_u32),
+            result(5, 1, "MCD".to_string()),
+            result(1, 2, 45000_u32),
+        ];
+        assert_eq!(
+            format_table(&answers, &results),
+            "\
+Day  Part  Result   Answer
+  1     1  pass     24000
+  5     1  FAIL     MCD (expected CMZ)
+  1     2  missing  45000
+"
+        );
+    }
+
+    #[test]
+    fn stored_answers() {
+        let input_dir = InputDir::default();
+        let text = std::fs::read_to_string(input_dir.path().join(ANSWERS_FILE)).unwrap();
+        let answers = parse(&text).unwrap();
+        for day in DAYS {
+            let text = input_dir.load(day.day()).unwrap();
+            for result in runner::solve(day, &text, &[1, 2]) {
+                let result = result.unwrap();
+                assert_eq!(answers.verify(&result), Verdict::Pass, "{result:?}");
+            }
+        }
+    }
+}
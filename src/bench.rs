@@ -0,0 +1,135 @@
+/* Copyright (C) 2022 Casper Meijn <casper@meijn.net>
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use crate::solution::{DayError, DynSolution};
+use std::{
+    fmt,
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+/// Spread of the durations measured over repeated runs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Timings {
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration,
+}
+
+impl Timings {
+    pub fn from_samples(samples: &[Duration]) -> Self {
+        let mut samples = samples.to_vec();
+        samples.sort_unstable();
+        Self {
+            min: samples.first().copied().unwrap_or_default(),
+            median: samples.get(samples.len() / 2).copied().unwrap_or_default(),
+            max: samples.last().copied().unwrap_or_default(),
+        }
+    }
+}
+
+/// Timings of the separate phases of a single day.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DayBenchmark {
+    pub day: u8,
+    pub iterations: usize,
+    pub parse: Timings,
+    pub challange1: Timings,
+    pub challange2: Timings,
+}
+
+impl DayBenchmark {
+    pub const HEADER: &'static str = "Day  Phase                 Min       Median          Max";
+}
+
+impl fmt::Display for DayBenchmark {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let phases = [
+            ("parse", self.parse),
+            ("challange 1", self.challange1),
+            ("challange 2", self.challange2),
+        ];
+        for (i, (phase, timings)) in phases.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(
+                f,
+                "{:>3}  {phase:<12} {:>12.1?} {:>12.1?} {:>12.1?}",
+                self.day, timings.min, timings.median, timings.max
+            )?;
+        }
+        Ok(())
+    }
+}
+
+fn measure<T>(samples: &mut Vec<Duration>, f: impl FnOnce() -> T) -> T {
+    let start = Instant::now();
+    let result = black_box(f());
+    samples.push(start.elapsed());
+    result
+}
+
+/// Parses and solves a day `iterations` times, timing each phase separately.
+pub fn bench(
+    day: &dyn DynSolution,
+    text: &str,
+    iterations: usize,
+) -> Result<DayBenchmark, DayError> {
+    let mut parse = Vec::with_capacity(iterations);
+    let mut challange1 = Vec::with_capacity(iterations);
+    let mut challange2 = Vec::with_capacity(iterations);
+    for _ in 0..iterations {
+        let input = measure(&mut parse, || day.parse(black_box(text)))?;
+        measure(&mut challange1, || day.challange1(input.as_ref()))
+            .map_err(|error| DayError::Solve { part: 1, error })?;
+        measure(&mut challange2, || day.challange2(input.as_ref()))
+            .map_err(|error| DayError::Solve { part: 2, error })?;
+    }
+    Ok(DayBenchmark {
+        day: day.day(),
+        iterations,
+        parse: Timings::from_samples(&parse),
+        challange1: Timings::from_samples(&challange1),
+        challange2: Timings::from_samples(&challange2),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn timings_from_samples() {
+        let samples = [5, 1, 4, 2, 3].map(Duration::from_millis);
+        let timings = Timings::from_samples(&samples);
+        assert_eq!(timings.min, Duration::from_millis(1));
+        assert_eq!(timings.median, Duration::from_millis(3));
+        assert_eq!(timings.max, Duration::from_millis(5));
+    }
+
+    #[test]
+    fn bench_example() {
+        let day = crate::day(6).unwrap();
+        let benchmark = bench(day, "mjqjpqmgbljsphdztnvjfqwrcgsmlb", 3).unwrap();
+        assert_eq!(benchmark.day, 6);
+        assert_eq!(benchmark.iterations, 3);
+        assert!(benchmark.parse.min <= benchmark.parse.median);
+        assert!(benchmark.parse.median <= benchmark.parse.max);
+        assert_eq!(benchmark.to_string().lines().count(), 3);
+    }
+}
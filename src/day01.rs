@@ -15,21 +15,303 @@
  * along with this program.  If not, see <http://www.gnu.org/licenses/>.
  */
 
-#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+use crate::{
+    parse::{Line, ParseError, ReadError},
+    solution::{Answer, Solution, SolveError},
+};
+use std::{
+    borrow::Borrow, cmp::Ordering, cmp::Reverse, collections::BinaryHeap, io::BufRead,
+    iter::FusedIterator,
+};
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Elf {
     total_calories: u32,
+    items: Vec<u32>,
+}
+
+impl Elf {
+    /// # Panics
+    ///
+    /// Panics when the total calories don't fit in a `u32`.
+    pub fn new(items: Vec<u32>) -> Self {
+        Self {
+            total_calories: items
+                .iter()
+                .try_fold(0u32, |total, &calories| total.checked_add(calories))
+                .expect("total calories overflow"),
+            items,
+        }
+    }
+
+    pub fn total_calories(&self) -> u32 {
+        self.total_calories
+    }
+
+    /// Calories of each food item, in the order of the inventory.
+    pub fn items(&self) -> &[u32] {
+        &self.items
+    }
+
+    /// Adds an item, unless the total calories would no longer fit in a `u32`.
+    fn push_item(&mut self, calories: u32) -> Option<()> {
+        self.total_calories = self.total_calories.checked_add(calories)?;
+        self.items.push(calories);
+        Some(())
+    }
+}
+
+pub fn parse(text: &str) -> Result<Vec<Elf>, ParseError> {
+    let elves = parse_reader(text.as_bytes())
+        .map(|elf| {
+            elf.map_err(|error| match error {
+                ReadError::Parse(error) => error,
+                ReadError::Io(error) => unreachable!("reading a string failed: {error}"),
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    if elves.is_empty() {
+        return Err(ParseError::end_of_input(text, "a calorie count"));
+    }
+    Ok(elves)
+}
+
+/// Reads elves one at a time, so the whole inventory never needs to be in memory.
+///
+/// Lines may end in `\r\n` and trailing whitespace is ignored. Elves are separated by one or more
+/// blank lines. The iterator ends after the first error.
+pub fn parse_reader<R: BufRead>(reader: R) -> ElfReader<R> {
+    ElfReader {
+        reader,
+        buffer: String::new(),
+        line_number: 0,
+        failed: false,
+    }
+}
+
+/// Iterator over the elves of an inventory, see [`parse_reader`].
+#[derive(Debug)]
+pub struct ElfReader<R> {
+    reader: R,
+    buffer: String,
+    line_number: usize,
+    failed: bool,
+}
+
+impl<R: BufRead> Iterator for ElfReader<R> {
+    type Item = Result<Elf, ReadError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        let result = self.next_elf();
+        self.failed = matches!(result, Some(Err(_)));
+        result
+    }
+}
+
+impl<R: BufRead> FusedIterator for ElfReader<R> {}
+
+impl<R: BufRead> ElfReader<R> {
+    fn next_elf(&mut self) -> Option<Result<Elf, ReadError>> {
+        let mut elf: Option<Elf> = None;
+        loop {
+            self.buffer.clear();
+            match self.reader.read_line(&mut self.buffer) {
+                Ok(0) => return elf.map(Ok),
+                Ok(_) => self.line_number += 1,
+                Err(error) => return Some(Err(error.into())),
+            }
+            let text = self.buffer.trim_end();
+            if text.is_empty() {
+                if elf.is_some() {
+                    return elf.map(Ok);
+                }
+                continue;
+            }
+            let line = Line::new(self.line_number, text);
+            let calories = match line.parse::<u32>(text, "an amount of calories") {
+                Ok(calories) => calories,
+                Err(error) => return Some(Err(error.into())),
+            };
+            let elf = elf.get_or_insert_with(Elf::default);
+            if elf.push_item(calories).is_none() {
+                let remaining = u32::MAX - elf.total_calories;
+                let expected = format!("at most {remaining} calories to fit in the elf's total");
+                return Some(Err(line.error(text, expected).into()));
+            }
+        }
+    }
+}
+
+/// An elf together with its position in the inventory, counting from 0.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RankedElf<E> {
+    pub index: usize,
+    pub elf: E,
+}
+
+impl<E: Borrow<Elf>> RankedElf<E> {
+    /// Orders by calories, where the earlier elf wins a tie.
+    fn rank(&self) -> (u32, Reverse<usize>) {
+        (self.elf.borrow().total_calories, Reverse(self.index))
+    }
 }
 
-pub fn parse(text: &str) -> Vec<Elf> {
-    text.split("\n\n").map(parse_elf).collect()
+/// Entry of the bounded heap in [`top_n`].
+struct HeapEntry<E>(RankedElf<E>);
+
+impl<E: Borrow<Elf>> PartialEq for HeapEntry<E> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<E: Borrow<Elf>> Eq for HeapEntry<E> {}
+
+impl<E: Borrow<Elf>> PartialOrd for HeapEntry<E> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<E: Borrow<Elf>> Ord for HeapEntry<E> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Reversed, so the heap keeps the lowest ranked elf on top
+        other.0.rank().cmp(&self.0.rank())
+    }
+}
+
+/// Finds the `n` elves carrying the most calories, ranked from most to least.
+///
+/// Only `n` elves are kept in memory, so this also works on the output of [`parse_reader`] and
+/// runs in O(len·log n).
+pub fn top_n<E: Borrow<Elf>>(elves: impl IntoIterator<Item = E>, n: usize) -> Vec<RankedElf<E>> {
+    if n == 0 {
+        return Vec::new();
+    }
+    let mut heap = BinaryHeap::with_capacity(n + 1);
+    for (index, elf) in elves.into_iter().enumerate() {
+        let entry = HeapEntry(RankedElf { index, elf });
+        if heap.len() < n {
+            heap.push(entry);
+        } else if let Some(mut lowest) = heap.peek_mut() {
+            if entry < *lowest {
+                *lowest = entry;
+            }
+        }
+    }
+    heap.into_sorted_vec()
+        .into_iter()
+        .map(|HeapEntry(ranked)| ranked)
+        .collect()
+}
+
+/// Range of total calories `start..end` and the number of elves carrying that amount.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Bucket {
+    pub start: u32,
+    /// Exclusive end, which may be just past `u32::MAX`.
+    pub end: u64,
+    pub count: usize,
 }
 
-fn parse_elf(text: &str) -> Elf {
-    let total_calories = text
-        .split('\n')
-        .map(|calories_text| calories_text.parse::<u32>().unwrap())
-        .sum();
-    Elf { total_calories }
+/// Statistics over the total calories of a population of elves.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Statistics {
+    totals: Vec<u32>,
+    sorted: Vec<u32>,
+}
+
+impl Statistics {
+    pub fn new(elves: &[Elf]) -> Self {
+        let totals = elves.iter().map(Elf::total_calories).collect::<Vec<_>>();
+        let mut sorted = totals.clone();
+        sorted.sort_unstable();
+        Self { totals, sorted }
+    }
+
+    pub fn len(&self) -> usize {
+        self.totals.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.totals.is_empty()
+    }
+
+    pub fn min(&self) -> Option<u32> {
+        self.sorted.first().copied()
+    }
+
+    pub fn max(&self) -> Option<u32> {
+        self.sorted.last().copied()
+    }
+
+    pub fn mean(&self) -> Option<f64> {
+        let sum = self
+            .totals
+            .iter()
+            .map(|&total| u64::from(total))
+            .sum::<u64>();
+        (!self.is_empty()).then(|| sum as f64 / self.len() as f64)
+    }
+
+    pub fn median(&self) -> Option<f64> {
+        self.percentile(50.0)
+    }
+
+    /// Interpolates linearly between the two closest elves, `percentile` ranges from 0 to 100.
+    pub fn percentile(&self, percentile: f64) -> Option<f64> {
+        assert!(
+            (0.0..=100.0).contains(&percentile),
+            "percentile {percentile} out of range"
+        );
+        if self.is_empty() {
+            return None;
+        }
+        let rank = percentile / 100.0 * (self.len() - 1) as f64;
+        let lower = f64::from(self.sorted[rank.floor() as usize]);
+        let upper = f64::from(self.sorted[rank.ceil() as usize]);
+        Some(lower + (upper - lower) * rank.fract())
+    }
+
+    /// Counts the elves per range of `bucket_size` calories, from the least to the most loaded elf.
+    pub fn histogram(&self, bucket_size: u32) -> Vec<Bucket> {
+        assert!(bucket_size > 0, "bucket size must be positive");
+        let (Some(min), Some(max)) = (self.min(), self.max()) else {
+            return Vec::new();
+        };
+        let first = min / bucket_size;
+        let last = max / bucket_size;
+        let mut buckets = (first..=last)
+            .map(|i| Bucket {
+                start: i * bucket_size,
+                end: u64::from(i * bucket_size) + u64::from(bucket_size),
+                count: 0,
+            })
+            .collect::<Vec<_>>();
+        for total in &self.sorted {
+            buckets[(total / bucket_size - first) as usize].count += 1;
+        }
+        buckets
+    }
+
+    /// Indices of elves outside of Tukey's fences: more than 1.5 times the interquartile range
+    /// below the first or above the third quartile.
+    pub fn outliers(&self) -> Vec<usize> {
+        let (Some(q1), Some(q3)) = (self.percentile(25.0), self.percentile(75.0)) else {
+            return Vec::new();
+        };
+        let iqr = q3 - q1;
+        let fences = (q1 - 1.5 * iqr)..=(q3 + 1.5 * iqr);
+        self.totals
+            .iter()
+            .enumerate()
+            .filter(|(_, &total)| !fences.contains(&f64::from(total)))
+            .map(|(index, _)| index)
+            .collect()
+    }
 }
 
 pub fn challange1(elves: &[Elf]) -> u32 {
@@ -39,12 +321,31 @@ pub fn challange1(elves: &[Elf]) -> u32 {
 }
 
 pub fn challange2(elves: &[Elf]) -> u32 {
-    let mut sorted_elves = elves.to_vec();
-    sorted_elves.sort_by_key(|elf| elf.total_calories);
-    sorted_elves.reverse();
+    let top3_elves = top_n(elves, 3);
+    top3_elves
+        .iter()
+        .map(|ranked| ranked.elf.total_calories)
+        .sum()
+}
+
+pub struct Day01;
 
-    let top3_elves = sorted_elves.iter().take(3);
-    top3_elves.map(|elf| elf.total_calories).sum()
+impl Solution for Day01 {
+    const DAY: u8 = 1;
+
+    type Input = Vec<Elf>;
+
+    fn parse(text: &str) -> Result<Self::Input, ParseError> {
+        parse(text)
+    }
+
+    fn challange1(input: &Self::Input) -> Result<Answer, SolveError> {
+        Ok(challange1(input).into())
+    }
+
+    fn challange2(input: &Self::Input) -> Result<Answer, SolveError> {
+        Ok(challange2(input).into())
+    }
 }
 
 #[cfg(test)]
@@ -69,38 +370,186 @@ mod tests {
 
     #[test]
     fn parse_example() {
-        let elves = parse(EXAMPLE_TEXT);
+        let elves = parse(EXAMPLE_TEXT).unwrap();
         assert_eq!(
             elves,
             vec!(
-                Elf {
-                    total_calories: 6000,
+                Elf::new(vec![1000, 2000, 3000]),
+                Elf::new(vec![4000]),
+                Elf::new(vec![5000, 6000]),
+                Elf::new(vec![7000, 8000, 9000]),
+                Elf::new(vec![10000]),
+            )
+        );
+        assert_eq!(elves[2].total_calories(), 11000);
+        assert_eq!(elves[2].items(), [5000, 6000]);
+    }
+
+    #[test]
+    fn parse_invalid() {
+        let error = parse("\n\n").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 3, column 1: expected a calorie count"
+        );
+        let error = parse("1000\n\n20x0").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 3, column 1: expected an amount of calories, found `20x0`"
+        );
+    }
+
+    #[test]
+    fn parse_reader_example() {
+        let text = EXAMPLE_TEXT.replace('\n', " \r\n") + "\r\n\r\n";
+        let elves = parse_reader(text.as_bytes())
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(elves, parse(EXAMPLE_TEXT).unwrap());
+
+        let mut elves = parse_reader("\n\n100\n\n\n200\n300".as_bytes());
+        assert_eq!(elves.next().unwrap().unwrap().total_calories, 100);
+        assert_eq!(elves.next().unwrap().unwrap().total_calories, 500);
+        assert!(elves.next().is_none());
+    }
+
+    #[test]
+    fn parse_reader_invalid() {
+        let mut elves = parse_reader("100\n\n2x0\n".as_bytes());
+        assert_eq!(elves.next().unwrap().unwrap().total_calories, 100);
+        let error = elves.next().unwrap().unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 3, column 1: expected an amount of calories, found `2x0`"
+        );
+        assert!(elves.next().is_none());
+    }
+
+    #[test]
+    fn parse_reader_overflow() {
+        let text = "1\n\n4000000000\n294967295\n1\n\n2\n";
+        let mut elves = parse_reader(text.as_bytes());
+        assert_eq!(elves.next().unwrap().unwrap().total_calories, 1);
+        let error = elves.next().unwrap().unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 5, column 1: expected at most 0 calories to fit in the elf's total, found `1`"
+        );
+        assert!(elves.next().is_none());
+        assert!(parse(text).is_err());
+    }
+
+    #[test]
+    fn statistics_example() {
+        let elves = parse(EXAMPLE_TEXT).unwrap();
+        let statistics = Statistics::new(&elves);
+        assert_eq!(statistics.len(), 5);
+        assert_eq!(statistics.min(), Some(4000));
+        assert_eq!(statistics.max(), Some(24000));
+        assert_eq!(statistics.mean(), Some(11000.0));
+        assert_eq!(statistics.median(), Some(10000.0));
+        assert_eq!(statistics.percentile(25.0), Some(6000.0));
+        assert_eq!(statistics.percentile(90.0), Some(18800.0));
+        assert_eq!(statistics.outliers(), vec![3]);
+        assert_eq!(
+            statistics.histogram(5000),
+            vec![
+                Bucket {
+                    start: 0,
+                    end: 5000,
+                    count: 1
                 },
-                Elf {
-                    total_calories: 4000,
+                Bucket {
+                    start: 5000,
+                    end: 10000,
+                    count: 1
                 },
-                Elf {
-                    total_calories: 11000,
+                Bucket {
+                    start: 10000,
+                    end: 15000,
+                    count: 2
                 },
-                Elf {
-                    total_calories: 24000,
+                Bucket {
+                    start: 15000,
+                    end: 20000,
+                    count: 0
                 },
-                Elf {
-                    total_calories: 10000,
+                Bucket {
+                    start: 20000,
+                    end: 25000,
+                    count: 1
                 },
-            )
+            ]
         );
     }
 
+    #[test]
+    fn statistics_empty() {
+        let statistics = Statistics::new(&[]);
+        assert!(statistics.is_empty());
+        assert_eq!(statistics.mean(), None);
+        assert_eq!(statistics.median(), None);
+        assert_eq!(statistics.histogram(100), vec![]);
+
+        let elves = [Elf::new(vec![u32::MAX]), Elf::new(vec![u32::MAX - 10])];
+        let statistics = Statistics::new(&elves);
+        assert_eq!(
+            statistics.histogram(100),
+            vec![Bucket {
+                start: 4294967200,
+                end: 4294967300,
+                count: 2
+            }]
+        );
+        assert_eq!(statistics.outliers(), Vec::<usize>::new());
+    }
+
     #[test]
     fn challange1_example() {
-        let elves = parse(EXAMPLE_TEXT);
+        let elves = parse(EXAMPLE_TEXT).unwrap();
         assert_eq!(challange1(&elves), 24000);
     }
 
+    #[test]
+    fn top_n_example() {
+        let elves = parse(EXAMPLE_TEXT).unwrap();
+        let top = top_n(&elves, 3);
+        assert_eq!(
+            top.iter()
+                .map(|ranked| (ranked.index, ranked.elf.total_calories()))
+                .collect::<Vec<_>>(),
+            vec![(3, 24000), (2, 11000), (4, 10000)]
+        );
+        assert_eq!(top_n(&elves, 0), vec![]);
+        assert_eq!(top_n(&elves, 10).len(), 5);
+    }
+
+    #[test]
+    fn top_n_tie() {
+        let elves = parse("5\n\n7\n\n5\n\n7\n\n1").unwrap();
+        let indices = top_n(&elves, 3)
+            .iter()
+            .map(|ranked| ranked.index)
+            .collect::<Vec<_>>();
+        assert_eq!(indices, vec![1, 3, 0]);
+    }
+
+    #[test]
+    fn top_n_reader() {
+        let elves = parse_reader(EXAMPLE_TEXT.as_bytes()).map(Result::unwrap);
+        let top = top_n(elves, 1);
+        assert_eq!(
+            top,
+            vec![RankedElf {
+                index: 3,
+                elf: Elf::new(vec![7000, 8000, 9000]),
+            }]
+        );
+    }
+
     #[test]
     fn challange2_example() {
-        let elves = parse(EXAMPLE_TEXT);
+        let elves = parse(EXAMPLE_TEXT).unwrap();
         assert_eq!(challange2(&elves), 45000);
     }
 }
@@ -15,237 +15,908 @@
  * along with this program.  If not, see <http://www.gnu.org/licenses/>.
  */
 
-#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
-enum Shape {
+use crate::{
+    parse::{lines, Line, ParseError},
+    solution::{Answer, Solution, SolveError},
+};
+use std::{borrow::Cow, fmt};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Shape {
     Rock,
     Paper,
     Scissors,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
-enum Winner {
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Winner {
     Opponent,
     Myself,
     Draw,
 }
 
-fn parse_shape(text: &str) -> Shape {
-    match text.chars().next().unwrap() {
-        'A' => Shape::Rock,
-        'B' => Shape::Paper,
-        'C' => Shape::Scissors,
-        'X' => Shape::Rock,
-        'Y' => Shape::Paper,
-        'Z' => Shape::Scissors,
-        _ => panic!(),
+impl Shape {
+    pub const ALL: [Shape; 3] = [Shape::Rock, Shape::Paper, Shape::Scissors];
+
+    /// Position of the shape in [`ROCK_PAPER_SCISSORS`].
+    pub fn index(self) -> usize {
+        match self {
+            Shape::Rock => 0,
+            Shape::Paper => 1,
+            Shape::Scissors => 2,
+        }
+    }
+
+    pub fn from_index(index: usize) -> Self {
+        Shape::ALL[index]
     }
 }
 
-pub mod challange1 {
-    use super::*;
+/// Points awarded for the result of a round.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OutcomeScores {
+    pub lose: u32,
+    pub draw: u32,
+    pub win: u32,
+}
 
-    #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
-    pub struct Round {
-        opponent: Shape,
-        mine: Shape,
+/// A cyclic game with an odd number of shapes, identified by their index.
+///
+/// Every shape beats the half of the other shapes that come before it in the cycle, and loses
+/// against the half that comes after it. With three shapes this is rock-paper-scissors.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RuleSet {
+    shape_scores: Cow<'static, [u32]>,
+    outcome_scores: OutcomeScores,
+}
+
+const DEFAULT_OUTCOME_SCORES: OutcomeScores = OutcomeScores {
+    lose: 0,
+    draw: 3,
+    win: 6,
+};
+
+/// Rock (0), paper (1) and scissors (2), scored as in the puzzle.
+pub const ROCK_PAPER_SCISSORS: RuleSet = RuleSet {
+    shape_scores: Cow::Borrowed(&[1, 2, 3]),
+    outcome_scores: DEFAULT_OUTCOME_SCORES,
+};
+
+/// Rock (0), Spock (1), paper (2), lizard (3) and scissors (4).
+pub const ROCK_PAPER_SCISSORS_LIZARD_SPOCK: RuleSet = RuleSet {
+    shape_scores: Cow::Borrowed(&[1, 2, 3, 4, 5]),
+    outcome_scores: DEFAULT_OUTCOME_SCORES,
+};
+
+impl RuleSet {
+    /// Creates a game of `shape_count` shapes, where shape `i` scores `i + 1` points.
+    pub fn cyclic(shape_count: usize) -> Self {
+        assert!(
+            shape_count % 2 == 1,
+            "a cyclic game needs an odd number of shapes, not {shape_count}"
+        );
+        Self {
+            shape_scores: (1..=shape_count as u32).collect(),
+            outcome_scores: DEFAULT_OUTCOME_SCORES,
+        }
     }
 
-    impl Round {
-        fn score(&self) -> u32 {
-            let shape_score = match self.mine {
-                Shape::Rock => 1,
-                Shape::Paper => 2,
-                Shape::Scissors => 3,
-            };
-            let result_score = match self.result() {
-                Winner::Opponent => 0,
-                Winner::Myself => 6,
-                Winner::Draw => 3,
-            };
-            shape_score + result_score
+    pub fn with_shape_scores(self, shape_scores: impl Into<Cow<'static, [u32]>>) -> Self {
+        let shape_scores = shape_scores.into();
+        assert_eq!(
+            shape_scores.len(),
+            self.shape_count(),
+            "expected a score for every shape"
+        );
+        Self {
+            shape_scores,
+            ..self
         }
+    }
 
-        fn result(&self) -> Winner {
-            match self.opponent {
-                Shape::Rock => match self.mine {
-                    Shape::Rock => Winner::Draw,
-                    Shape::Paper => Winner::Myself,
-                    Shape::Scissors => Winner::Opponent,
-                },
-                Shape::Paper => match self.mine {
-                    Shape::Rock => Winner::Opponent,
-                    Shape::Paper => Winner::Draw,
-                    Shape::Scissors => Winner::Myself,
-                },
-                Shape::Scissors => match self.mine {
-                    Shape::Rock => Winner::Myself,
-                    Shape::Paper => Winner::Opponent,
-                    Shape::Scissors => Winner::Draw,
-                },
-            }
+    pub fn with_outcome_scores(self, outcome_scores: OutcomeScores) -> Self {
+        Self {
+            outcome_scores,
+            ..self
         }
     }
 
-    pub fn parse(text: &str) -> Vec<Round> {
-        text.lines().map(parse_round).collect()
+    pub fn shape_count(&self) -> usize {
+        self.shape_scores.len()
+    }
+
+    fn check_shape(&self, shape: usize) {
+        assert!(
+            shape < self.shape_count(),
+            "shape {shape} doesn't exist in a game of {} shapes",
+            self.shape_count()
+        );
     }
 
-    fn parse_round(text: &str) -> Round {
-        let (opponent_text, mine_text) = text.split_once(' ').unwrap();
-        Round {
-            opponent: parse_shape(opponent_text),
-            mine: parse_shape(mine_text),
+    /// # Panics
+    ///
+    /// Panics when `opponent` or `mine` is not below [`shape_count`](Self::shape_count).
+    pub fn result(&self, opponent: usize, mine: usize) -> Winner {
+        self.check_shape(opponent);
+        self.check_shape(mine);
+        let count = self.shape_count();
+        let distance = (mine + count - opponent) % count;
+        if distance == 0 {
+            Winner::Draw
+        } else if distance <= count / 2 {
+            Winner::Myself
+        } else {
+            Winner::Opponent
         }
     }
 
-    pub fn challange1(rounds: &[Round]) -> u32 {
-        rounds.iter().map(Round::score).sum()
+    /// Picks the shape that gives `result` against `opponent`.
+    ///
+    /// When multiple shapes qualify, the one closest to `opponent` in the cycle is used.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `opponent` is not below [`shape_count`](Self::shape_count).
+    pub fn shape_for(&self, opponent: usize, result: Winner) -> usize {
+        self.check_shape(opponent);
+        let count = self.shape_count();
+        match result {
+            Winner::Draw => opponent,
+            Winner::Myself => (opponent + 1) % count,
+            Winner::Opponent => (opponent + count - 1) % count,
+        }
     }
 
-    #[cfg(test)]
-    mod tests {
-        use super::*;
+    /// Scores a round from my point of view.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `opponent` or `mine` is not below [`shape_count`](Self::shape_count).
+    pub fn score(&self, opponent: usize, mine: usize) -> u32 {
+        let result_score = match self.result(opponent, mine) {
+            Winner::Opponent => self.outcome_scores.lose,
+            Winner::Myself => self.outcome_scores.win,
+            Winner::Draw => self.outcome_scores.draw,
+        };
+        self.shape_scores[mine] + result_score
+    }
+}
 
-        const EXAMPLE_TEXT: &str = "\
-A Y
-B X
-C Z";
+/// Second column of the strategy guide, which only gets a meaning through an [`Interpretation`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Column {
+    X,
+    Y,
+    Z,
+}
 
-        #[test]
-        fn parse_example() {
-            let rounds = parse(EXAMPLE_TEXT);
-            assert_eq!(
-                rounds,
-                vec!(
-                    Round {
-                        opponent: Shape::Rock,
-                        mine: Shape::Paper,
-                    },
-                    Round {
-                        opponent: Shape::Paper,
-                        mine: Shape::Rock,
-                    },
-                    Round {
-                        opponent: Shape::Scissors,
-                        mine: Shape::Scissors,
-                    },
-                )
-            );
+impl Column {
+    fn index(self) -> usize {
+        match self {
+            Column::X => 0,
+            Column::Y => 1,
+            Column::Z => 2,
         }
+    }
+}
 
-        #[test]
-        fn challange1_example() {
-            let rounds = parse(EXAMPLE_TEXT);
-            assert_eq!(challange1(&rounds), 15);
-        }
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Round {
+    pub opponent: Shape,
+    pub column: Column,
+}
+
+/// A way to read the second column of the strategy guide.
+pub trait Interpretation {
+    /// Picks the index of my shape in `rules` for a round.
+    fn mine(&self, rules: &RuleSet, round: &Round) -> usize;
+}
+
+/// Reads `X`, `Y` and `Z` as the shape to play.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AsShape(pub [Shape; 3]);
+
+/// Reads `X`, `Y` and `Z` as the result the round needs to end in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AsResult(pub [Winner; 3]);
+
+/// The interpretation of challange 1: `X` is rock, `Y` is paper and `Z` is scissors.
+pub const AS_SHAPE: AsShape = AsShape([Shape::Rock, Shape::Paper, Shape::Scissors]);
+
+/// The interpretation of challange 2: `X` is lose, `Y` is draw and `Z` is win.
+pub const AS_RESULT: AsResult = AsResult([Winner::Opponent, Winner::Draw, Winner::Myself]);
+
+impl Interpretation for AsShape {
+    fn mine(&self, _rules: &RuleSet, round: &Round) -> usize {
+        self.0[round.column.index()].index()
     }
 }
 
-pub mod challange2 {
-    use super::*;
+impl Interpretation for AsResult {
+    fn mine(&self, rules: &RuleSet, round: &Round) -> usize {
+        rules.shape_for(round.opponent.index(), self.0[round.column.index()])
+    }
+}
 
-    #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
-    pub struct Round {
-        opponent: Shape,
-        result: Winner,
+impl Round {
+    pub fn score(&self, rules: &RuleSet, interpretation: &dyn Interpretation) -> u32 {
+        rules.score(self.opponent.index(), interpretation.mine(rules, self))
     }
+}
 
-    impl Round {
-        fn score(&self) -> u32 {
-            let shape_score = match self.mine() {
-                Shape::Rock => 1,
-                Shape::Paper => 2,
-                Shape::Scissors => 3,
-            };
-            let result_score = match self.result {
-                Winner::Opponent => 0,
-                Winner::Myself => 6,
-                Winner::Draw => 3,
+/// Total score of following the strategy guide, when read with `interpretation`.
+pub fn total_score(rounds: &[Round], rules: &RuleSet, interpretation: &dyn Interpretation) -> u32 {
+    rounds
+        .iter()
+        .map(|round| round.score(rules, interpretation))
+        .sum()
+}
+
+/// All orders of three items, used to try every mapping of `X`, `Y` and `Z`.
+const PERMUTATIONS: [[usize; 3]; 6] = [
+    [0, 1, 2],
+    [0, 2, 1],
+    [1, 0, 2],
+    [1, 2, 0],
+    [2, 0, 1],
+    [2, 1, 0],
+];
+
+impl AsShape {
+    /// Every way to map `X`, `Y` and `Z` to a different shape.
+    pub fn permutations() -> [AsShape; 6] {
+        let shapes = AS_SHAPE.0;
+        PERMUTATIONS.map(|order| AsShape(order.map(|i| shapes[i])))
+    }
+}
+
+impl AsResult {
+    /// Every way to map `X`, `Y` and `Z` to a different result.
+    pub fn permutations() -> [AsResult; 6] {
+        let results = AS_RESULT.0;
+        PERMUTATIONS.map(|order| AsResult(order.map(|i| results[i])))
+    }
+}
+
+impl fmt::Display for AsShape {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let [x, y, z] = self.0;
+        write!(f, "X={x:?}, Y={y:?}, Z={z:?}")
+    }
+}
+
+impl fmt::Display for AsResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = |result| match result {
+            Winner::Opponent => "lose",
+            Winner::Draw => "draw",
+            Winner::Myself => "win",
+        };
+        let [x, y, z] = self.0.map(name);
+        write!(f, "X={x}, Y={y}, Z={z}")
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Goal {
+    Maximize,
+    Minimize,
+}
+
+/// Result of trying multiple interpretations of a strategy guide.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Optimization<I> {
+    pub best: I,
+    pub best_score: u32,
+    /// Total score of every candidate, in the order they were tried.
+    pub scores: Vec<(I, u32)>,
+}
+
+/// Scores the strategy guide with every candidate interpretation and picks the best one.
+///
+/// On a tie the earliest candidate is picked.
+pub fn optimize<I: Interpretation + Copy>(
+    rounds: &[Round],
+    rules: &RuleSet,
+    candidates: &[I],
+    goal: Goal,
+) -> Optimization<I> {
+    assert!(!candidates.is_empty(), "no candidates to optimize over");
+    let scores = candidates
+        .iter()
+        .map(|candidate| (*candidate, total_score(rounds, rules, candidate)))
+        .collect::<Vec<_>>();
+    let (best, best_score) = scores
+        .iter()
+        .copied()
+        .reduce(|best, candidate| {
+            let better = match goal {
+                Goal::Maximize => candidate.1 > best.1,
+                Goal::Minimize => candidate.1 < best.1,
             };
-            shape_score + result_score
+            if better {
+                candidate
+            } else {
+                best
+            }
+        })
+        .unwrap();
+    Optimization {
+        best,
+        best_score,
+        scores,
+    }
+}
+
+pub fn parse(text: &str) -> Result<Vec<Round>, ParseError> {
+    lines(text).map(parse_round).collect()
+}
+
+fn parse_round(line: Line) -> Result<Round, ParseError> {
+    let mut words = line.words();
+    let opponent = parse_shape(&line, words.next("a shape")?)?;
+    let column = parse_column(&line, words.next("`X`, `Y` or `Z`")?)?;
+    words.end()?;
+    Ok(Round { opponent, column })
+}
+
+fn parse_shape(line: &Line, text: &str) -> Result<Shape, ParseError> {
+    match text {
+        "A" => Ok(Shape::Rock),
+        "B" => Ok(Shape::Paper),
+        "C" => Ok(Shape::Scissors),
+        _ => Err(line.error(text, "a shape (`A`, `B` or `C`)")),
+    }
+}
+
+fn parse_column(line: &Line, text: &str) -> Result<Column, ParseError> {
+    match text {
+        "X" => Ok(Column::X),
+        "Y" => Ok(Column::Y),
+        "Z" => Ok(Column::Z),
+        _ => Err(line.error(text, "`X`, `Y` or `Z`")),
+    }
+}
+
+pub fn challange1(rounds: &[Round]) -> u32 {
+    total_score(rounds, &ROCK_PAPER_SCISSORS, &AS_SHAPE)
+}
+
+pub fn challange2(rounds: &[Round]) -> u32 {
+    total_score(rounds, &ROCK_PAPER_SCISSORS, &AS_RESULT)
+}
+
+/// Simulation of many rounds between players that each follow their own strategy.
+pub mod simulation {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    /// SplitMix64, a small random number generator that gives the same numbers for the same seed.
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub struct Rng {
+        state: u64,
+    }
+
+    impl Rng {
+        pub fn new(seed: u64) -> Self {
+            Self { state: seed }
         }
 
-        fn mine(&self) -> Shape {
-            match self.opponent {
-                Shape::Rock => match self.result {
-                    Winner::Opponent => Shape::Scissors,
-                    Winner::Myself => Shape::Paper,
-                    Winner::Draw => Shape::Rock,
-                },
-                Shape::Paper => match self.result {
-                    Winner::Opponent => Shape::Rock,
-                    Winner::Myself => Shape::Scissors,
-                    Winner::Draw => Shape::Paper,
-                },
-                Shape::Scissors => match self.result {
-                    Winner::Opponent => Shape::Paper,
-                    Winner::Myself => Shape::Rock,
-                    Winner::Draw => Shape::Scissors,
-                },
+        pub fn next_u64(&mut self) -> u64 {
+            self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+            let mut z = self.state;
+            z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+            z ^ (z >> 31)
+        }
+
+        /// Returns a number in `0..bound`.
+        pub fn below(&mut self, bound: usize) -> usize {
+            (self.next_u64() % bound as u64) as usize
+        }
+    }
+
+    /// How a player picks a shape each round.
+    pub trait Strategy {
+        fn choose(&mut self) -> Shape;
+
+        /// Called after every round with the shape the other player chose.
+        fn observe(&mut self, _opponent: Shape) {}
+    }
+
+    /// Always plays the same shape.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub struct Fixed(pub Shape);
+
+    impl Strategy for Fixed {
+        fn choose(&mut self) -> Shape {
+            self.0
+        }
+    }
+
+    /// Plays a random shape every round.
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub struct Random {
+        rng: Rng,
+    }
+
+    impl Random {
+        pub fn new(seed: u64) -> Self {
+            Self {
+                rng: Rng::new(seed),
             }
         }
     }
 
-    pub fn parse(text: &str) -> Vec<Round> {
-        text.lines().map(parse_round).collect()
+    impl Strategy for Random {
+        fn choose(&mut self) -> Shape {
+            Shape::from_index(self.rng.below(Shape::ALL.len()))
+        }
+    }
+
+    /// Plays the shape that beats the shape the opponent played most often so far.
+    ///
+    /// Ties go to the first shape in [`Shape::ALL`].
+    #[derive(Clone, Debug, Default, PartialEq, Eq)]
+    pub struct FrequencyCounting {
+        counts: [usize; 3],
+    }
+
+    impl Strategy for FrequencyCounting {
+        fn choose(&mut self) -> Shape {
+            let most_frequent = (0..self.counts.len())
+                .reduce(|best, i| {
+                    if self.counts[i] > self.counts[best] {
+                        i
+                    } else {
+                        best
+                    }
+                })
+                .unwrap();
+            Shape::from_index(ROCK_PAPER_SCISSORS.shape_for(most_frequent, Winner::Myself))
+        }
+
+        fn observe(&mut self, opponent: Shape) {
+            self.counts[opponent.index()] += 1;
+        }
+    }
+
+    /// Plays my side of the strategy guide, starting over when the guide runs out.
+    ///
+    /// The guide is read with `interpretation`, assuming the opponent plays as the guide
+    /// predicts.
+    pub struct GuideFollowing<'a> {
+        rounds: &'a [Round],
+        interpretation: &'a dyn Interpretation,
+        next: usize,
+    }
+
+    impl<'a> GuideFollowing<'a> {
+        pub fn new(rounds: &'a [Round], interpretation: &'a dyn Interpretation) -> Self {
+            assert!(!rounds.is_empty(), "the strategy guide is empty");
+            Self {
+                rounds,
+                interpretation,
+                next: 0,
+            }
+        }
+    }
+
+    impl Strategy for GuideFollowing<'_> {
+        fn choose(&mut self) -> Shape {
+            let round = &self.rounds[self.next % self.rounds.len()];
+            self.next += 1;
+            Shape::from_index(self.interpretation.mine(&ROCK_PAPER_SCISSORS, round))
+        }
+    }
+
+    /// Plays the opponent's side of the strategy guide, starting over when the guide runs out.
+    pub struct GuideOpponent<'a> {
+        rounds: &'a [Round],
+        next: usize,
+    }
+
+    impl<'a> GuideOpponent<'a> {
+        pub fn new(rounds: &'a [Round]) -> Self {
+            assert!(!rounds.is_empty(), "the strategy guide is empty");
+            Self { rounds, next: 0 }
+        }
+    }
+
+    impl Strategy for GuideOpponent<'_> {
+        fn choose(&mut self) -> Shape {
+            let round = &self.rounds[self.next % self.rounds.len()];
+            self.next += 1;
+            round.opponent
+        }
+    }
+
+    /// Outcome of a simulation for one of the players.
+    #[derive(Clone, Debug, Default, PartialEq, Eq)]
+    pub struct PlayerReport {
+        pub wins: usize,
+        pub total_score: u64,
+        /// Number of rounds per round score.
+        pub score_counts: BTreeMap<u32, usize>,
     }
 
-    fn parse_round(text: &str) -> Round {
-        let (opponent_text, winner_text) = text.split_once(' ').unwrap();
-        Round {
-            opponent: parse_shape(opponent_text),
-            result: parse_winner(winner_text),
+    #[derive(Clone, Debug, Default, PartialEq, Eq)]
+    pub struct Report {
+        pub rounds: usize,
+        pub draws: usize,
+        pub players: [PlayerReport; 2],
+    }
+
+    impl Report {
+        pub fn win_rate(&self, player: usize) -> f64 {
+            self.players[player].wins as f64 / self.rounds as f64
+        }
+
+        pub fn draw_rate(&self) -> f64 {
+            self.draws as f64 / self.rounds as f64
+        }
+
+        pub fn mean_score(&self, player: usize) -> f64 {
+            self.players[player].total_score as f64 / self.rounds as f64
         }
     }
 
-    fn parse_winner(text: &str) -> Winner {
-        match text.chars().next().unwrap() {
-            'X' => Winner::Opponent,
-            'Y' => Winner::Draw,
-            'Z' => Winner::Myself,
-            _ => panic!(),
+    /// Plays `rounds` rounds between two players, scored with `rules`.
+    pub fn simulate(
+        rules: &RuleSet,
+        first: &mut dyn Strategy,
+        second: &mut dyn Strategy,
+        rounds: usize,
+    ) -> Report {
+        assert_eq!(
+            rules.shape_count(),
+            Shape::ALL.len(),
+            "players can only play rock, paper or scissors"
+        );
+        let mut report = Report {
+            rounds,
+            ..Default::default()
+        };
+        for _ in 0..rounds {
+            let shapes = [first.choose(), second.choose()];
+            first.observe(shapes[1]);
+            second.observe(shapes[0]);
+
+            match rules.result(shapes[1].index(), shapes[0].index()) {
+                Winner::Myself => report.players[0].wins += 1,
+                Winner::Opponent => report.players[1].wins += 1,
+                Winner::Draw => report.draws += 1,
+            }
+            for (player, (mine, other)) in
+                [(0, (shapes[0], shapes[1])), (1, (shapes[1], shapes[0]))]
+            {
+                let score = rules.score(other.index(), mine.index());
+                let player = &mut report.players[player];
+                player.total_score += u64::from(score);
+                *player.score_counts.entry(score).or_default() += 1;
+            }
         }
+        report
     }
 
-    pub fn challange2(rounds: &[Round]) -> u32 {
-        rounds.iter().map(Round::score).sum()
+    /// A named way to create a fresh player for every match of a tournament.
+    pub type Entrant<'a> = (&'a str, &'a dyn Fn() -> Box<dyn Strategy + 'a>);
+
+    /// Lets every entrant play `rounds` rounds against every other entrant.
+    ///
+    /// Returns a report for every pair of entrant indices `(i, j)` with `i < j`.
+    pub fn tournament(
+        rules: &RuleSet,
+        entrants: &[Entrant],
+        rounds: usize,
+    ) -> Vec<(usize, usize, Report)> {
+        let mut reports = Vec::new();
+        for i in 0..entrants.len() {
+            for j in i + 1..entrants.len() {
+                let mut first = (entrants[i].1)();
+                let mut second = (entrants[j].1)();
+                let report = simulate(rules, first.as_mut(), second.as_mut(), rounds);
+                reports.push((i, j, report));
+            }
+        }
+        reports
     }
 
     #[cfg(test)]
     mod tests {
         use super::*;
 
-        const EXAMPLE_TEXT: &str = "\
-A Y
-B X
-C Z";
+        #[test]
+        fn fixed_example() {
+            let report = simulate(
+                &ROCK_PAPER_SCISSORS,
+                &mut Fixed(Shape::Rock),
+                &mut Fixed(Shape::Scissors),
+                10,
+            );
+            assert_eq!(report.players[0].wins, 10);
+            assert_eq!(report.players[1].wins, 0);
+            assert_eq!(report.win_rate(0), 1.0);
+            assert_eq!(report.draw_rate(), 0.0);
+            assert_eq!(report.players[0].score_counts, BTreeMap::from([(7, 10)]));
+            assert_eq!(report.players[1].score_counts, BTreeMap::from([(3, 10)]));
+        }
 
         #[test]
-        fn parse_example() {
-            let rounds = parse(EXAMPLE_TEXT);
-            assert_eq!(
-                rounds,
-                vec!(
-                    Round {
-                        opponent: Shape::Rock,
-                        result: Winner::Draw,
-                    },
-                    Round {
-                        opponent: Shape::Paper,
-                        result: Winner::Opponent,
-                    },
-                    Round {
-                        opponent: Shape::Scissors,
-                        result: Winner::Myself,
-                    },
+        fn random_is_reproducible() {
+            let play = |seed| {
+                simulate(
+                    &ROCK_PAPER_SCISSORS,
+                    &mut Random::new(seed),
+                    &mut Fixed(Shape::Paper),
+                    3000,
                 )
+            };
+            let report = play(42);
+            assert_eq!(report, play(42));
+            assert_ne!(report, play(43));
+            for rate in [report.win_rate(0), report.win_rate(1), report.draw_rate()] {
+                assert!((0.28..0.39).contains(&rate), "{rate}");
+            }
+        }
+
+        #[test]
+        fn frequency_counting_beats_fixed() {
+            let report = simulate(
+                &ROCK_PAPER_SCISSORS,
+                &mut FrequencyCounting::default(),
+                &mut Fixed(Shape::Scissors),
+                100,
             );
+            assert_eq!(report.players[0].wins, 99);
+            assert_eq!(report.players[1].wins, 1);
+        }
+
+        #[test]
+        fn guide_following_example() {
+            let rounds = parse("A Y\nB X\nC Z").unwrap();
+            for (interpretation, score) in
+                [(&AS_SHAPE as &dyn Interpretation, 15), (&AS_RESULT, 12)]
+            {
+                let report = simulate(
+                    &ROCK_PAPER_SCISSORS,
+                    &mut GuideFollowing::new(&rounds, interpretation),
+                    &mut GuideOpponent::new(&rounds),
+                    6,
+                );
+                assert_eq!(report.players[0].total_score, 2 * score);
+            }
         }
 
         #[test]
-        fn challange2_example() {
-            let rounds = parse(EXAMPLE_TEXT);
-            assert_eq!(challange2(&rounds), 12);
+        fn tournament_example() {
+            let rock = || Box::new(Fixed(Shape::Rock)) as Box<dyn Strategy>;
+            let paper = || Box::new(Fixed(Shape::Paper)) as Box<dyn Strategy>;
+            let random = || Box::new(Random::new(1)) as Box<dyn Strategy>;
+            let entrants: [Entrant; 3] = [("rock", &rock), ("paper", &paper), ("random", &random)];
+            let reports = tournament(&ROCK_PAPER_SCISSORS, &entrants, 10);
+            assert_eq!(
+                reports.iter().map(|(i, j, _)| (*i, *j)).collect::<Vec<_>>(),
+                vec![(0, 1), (0, 2), (1, 2)]
+            );
+            assert_eq!(reports[0].2.players[1].wins, 10);
+        }
+    }
+}
+
+pub struct Day02;
+
+impl Solution for Day02 {
+    const DAY: u8 = 2;
+
+    type Input = Vec<Round>;
+
+    fn parse(text: &str) -> Result<Self::Input, ParseError> {
+        parse(text)
+    }
+
+    fn challange1(input: &Self::Input) -> Result<Answer, SolveError> {
+        Ok(challange1(input).into())
+    }
+
+    fn challange2(input: &Self::Input) -> Result<Answer, SolveError> {
+        Ok(challange2(input).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE_TEXT: &str = "\
+A Y
+B X
+C Z";
+
+    #[test]
+    fn parse_example() {
+        let rounds = parse(EXAMPLE_TEXT).unwrap();
+        assert_eq!(
+            rounds,
+            vec!(
+                Round {
+                    opponent: Shape::Rock,
+                    column: Column::Y,
+                },
+                Round {
+                    opponent: Shape::Paper,
+                    column: Column::X,
+                },
+                Round {
+                    opponent: Shape::Scissors,
+                    column: Column::Z,
+                },
+            )
+        );
+    }
+
+    #[test]
+    fn parse_invalid() {
+        let error = parse("A Y\nB Q").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 2, column 3: expected `X`, `Y` or `Z`, found `Q`"
+        );
+        let error = parse("X Y").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 1, column 1: expected a shape (`A`, `B` or `C`), found `X`"
+        );
+        let error = parse("A").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 1, column 2: expected `X`, `Y` or `Z`"
+        );
+        let error = parse("A Y\nB Y Z").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 2, column 5: expected end of line, found `Z`"
+        );
+    }
+
+    #[test]
+    fn interpretation_example() {
+        let rounds = parse(EXAMPLE_TEXT).unwrap();
+        let mine = |interpretation: &dyn Interpretation| {
+            rounds
+                .iter()
+                .map(|round| interpretation.mine(&ROCK_PAPER_SCISSORS, round))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            mine(&AS_SHAPE),
+            [Shape::Paper, Shape::Rock, Shape::Scissors].map(Shape::index)
+        );
+        assert_eq!(
+            mine(&AS_RESULT),
+            [Shape::Rock, Shape::Rock, Shape::Rock].map(Shape::index)
+        );
+    }
+
+    #[test]
+    fn optimize_shapes_example() {
+        let rounds = parse(EXAMPLE_TEXT).unwrap();
+        let candidates = AsShape::permutations();
+        let optimization = optimize(&rounds, &ROCK_PAPER_SCISSORS, &candidates, Goal::Maximize);
+        assert_eq!(optimization.best.to_string(), "X=Scissors, Y=Paper, Z=Rock");
+        assert_eq!(optimization.best_score, 24);
+        assert_eq!(
+            optimization
+                .scores
+                .iter()
+                .map(|(_, score)| *score)
+                .collect::<Vec<_>>(),
+            vec![15, 6, 15, 15, 15, 24]
+        );
+        assert_eq!(optimization.scores[0].0, AS_SHAPE);
+
+        let optimization = optimize(&rounds, &ROCK_PAPER_SCISSORS, &candidates, Goal::Minimize);
+        assert_eq!(optimization.best.to_string(), "X=Rock, Y=Scissors, Z=Paper");
+        assert_eq!(optimization.best_score, 6);
+    }
+
+    #[test]
+    fn optimize_results_example() {
+        let rounds = parse(EXAMPLE_TEXT).unwrap();
+        let candidates = AsResult::permutations();
+        let optimization = optimize(&rounds, &ROCK_PAPER_SCISSORS, &candidates, Goal::Maximize);
+        assert_eq!(optimization.best.to_string(), "X=win, Y=lose, Z=draw");
+        assert_eq!(optimization.best_score, 18);
+        assert_eq!(optimization.scores.len(), 6);
+        assert_eq!(optimization.scores[0], (AS_RESULT, 12));
+    }
+
+    #[test]
+    fn challange1_example() {
+        let rounds = parse(EXAMPLE_TEXT).unwrap();
+        assert_eq!(challange1(&rounds), 15);
+    }
+
+    #[test]
+    fn challange2_example() {
+        let rounds = parse(EXAMPLE_TEXT).unwrap();
+        assert_eq!(challange2(&rounds), 12);
+    }
+
+    #[test]
+    fn rock_paper_scissors_result() {
+        use Shape::*;
+        let rules = ROCK_PAPER_SCISSORS;
+        let result = |opponent: Shape, mine: Shape| rules.result(opponent.index(), mine.index());
+        assert_eq!(result(Rock, Rock), Winner::Draw);
+        assert_eq!(result(Rock, Paper), Winner::Myself);
+        assert_eq!(result(Rock, Scissors), Winner::Opponent);
+        assert_eq!(result(Paper, Rock), Winner::Opponent);
+        assert_eq!(result(Paper, Scissors), Winner::Myself);
+        assert_eq!(result(Scissors, Rock), Winner::Myself);
+        assert_eq!(result(Scissors, Paper), Winner::Opponent);
+    }
+
+    #[test]
+    fn rock_paper_scissors_lizard_spock_result() {
+        let rules = ROCK_PAPER_SCISSORS_LIZARD_SPOCK;
+        let (rock, spock, paper, lizard, scissors) = (0, 1, 2, 3, 4);
+        assert_eq!(rules.result(rock, spock), Winner::Myself);
+        assert_eq!(rules.result(rock, paper), Winner::Myself);
+        assert_eq!(rules.result(rock, lizard), Winner::Opponent);
+        assert_eq!(rules.result(rock, scissors), Winner::Opponent);
+        assert_eq!(rules.result(lizard, spock), Winner::Opponent);
+        assert_eq!(rules.result(spock, scissors), Winner::Opponent);
+        assert_eq!(rules.result(paper, paper), Winner::Draw);
+    }
+
+    #[test]
+    fn shape_for_gives_result() {
+        for rules in [RuleSet::cyclic(3), RuleSet::cyclic(5), RuleSet::cyclic(7)] {
+            for opponent in 0..rules.shape_count() {
+                for result in [Winner::Opponent, Winner::Myself, Winner::Draw] {
+                    let mine = rules.shape_for(opponent, result);
+                    assert_eq!(rules.result(opponent, mine), result);
+                }
+            }
         }
     }
+
+    #[test]
+    fn configurable_scores() {
+        let rules = ROCK_PAPER_SCISSORS
+            .with_shape_scores(vec![10, 20, 30])
+            .with_outcome_scores(OutcomeScores {
+                lose: 1,
+                draw: 2,
+                win: 3,
+            });
+        assert_eq!(rules.score(0, 1), 23);
+        assert_eq!(rules.score(1, 0), 11);
+        assert_eq!(rules.score(2, 2), 32);
+    }
+
+    #[test]
+    #[should_panic]
+    fn cyclic_even() {
+        RuleSet::cyclic(4);
+    }
+
+    #[test]
+    #[should_panic(expected = "shape 5 doesn't exist in a game of 3 shapes")]
+    fn result_invalid_shape() {
+        ROCK_PAPER_SCISSORS.result(5, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "shape 7 doesn't exist in a game of 3 shapes")]
+    fn score_invalid_shape() {
+        ROCK_PAPER_SCISSORS.score(0, 7);
+    }
+
+    #[test]
+    #[should_panic(expected = "shape 3 doesn't exist in a game of 3 shapes")]
+    fn shape_for_invalid_shape() {
+        ROCK_PAPER_SCISSORS.shape_for(3, Winner::Draw);
+    }
 }
@@ -15,67 +15,362 @@
  * along with this program.  If not, see <http://www.gnu.org/licenses/>.
  */
 
+use crate::{
+    parse::{lines, Line, ParseError},
+    solution::{Answer, Solution, SolveError},
+};
+use std::{error, fmt};
+
+/// A set of items, stored as a bit mask with one bit per item priority.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ItemSet(u64);
+
+impl ItemSet {
+    pub fn new() -> Self {
+        Self(0)
+    }
+
+    /// Creates a set from a list of items, or returns the first character that isn't an item.
+    pub fn try_from_items(items: &str) -> Result<Self, InvalidItem> {
+        let mut set = ItemSet::new();
+        for item in items.chars() {
+            set.try_insert(item)?;
+        }
+        Ok(set)
+    }
+
+    /// # Panics
+    ///
+    /// Panics when `item` isn't `a`-`z` or `A`-`Z`, see [`ItemSet::try_insert`].
+    pub fn insert(&mut self, item: char) {
+        self.try_insert(item).unwrap();
+    }
+
+    pub fn try_insert(&mut self, item: char) -> Result<(), InvalidItem> {
+        let priority = item_priority(item).ok_or(InvalidItem(item))?;
+        self.0 |= 1 << priority;
+        Ok(())
+    }
+
+    /// Returns whether `item` is in the set, which is never the case for an invalid item.
+    pub fn contains(&self, item: char) -> bool {
+        item_priority(item).is_some_and(|priority| self.0 & (1 << priority) != 0)
+    }
+
+    pub fn intersection(self, other: ItemSet) -> ItemSet {
+        ItemSet(self.0 & other.0)
+    }
+
+    pub fn union(self, other: ItemSet) -> ItemSet {
+        ItemSet(self.0 | other.0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    /// Returns the items in order of priority.
+    pub fn items(&self) -> impl Iterator<Item = char> {
+        let mask = self.0;
+        (1..=52)
+            .filter(move |priority| mask & (1 << priority) != 0)
+            .map(item_for_priority)
+    }
+
+    /// Returns the item with the lowest priority.
+    pub fn first(&self) -> Option<char> {
+        (!self.is_empty()).then(|| item_for_priority(self.0.trailing_zeros()))
+    }
+}
+
+/// # Panics
+///
+/// Panics on a character that isn't `a`-`z` or `A`-`Z`.
+impl FromIterator<char> for ItemSet {
+    fn from_iter<T: IntoIterator<Item = char>>(iter: T) -> Self {
+        let mut set = ItemSet::new();
+        for item in iter {
+            set.insert(item);
+        }
+        set
+    }
+}
+
+/// # Panics
+///
+/// Panics on a character that isn't `a`-`z` or `A`-`Z`, see [`ItemSet::try_from_items`].
+impl From<&str> for ItemSet {
+    fn from(items: &str) -> Self {
+        items.chars().collect()
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Rucksack {
-    all_items: String,
-    first_compartment: String,
-    second_compartment: String,
+    first_compartment: ItemSet,
+    second_compartment: ItemSet,
 }
 
-pub fn parse(text: &str) -> Vec<Rucksack> {
-    text.lines().map(parse_rucksack).collect()
+pub fn parse(text: &str) -> Result<Vec<Rucksack>, ParseError> {
+    lines(text).map(parse_rucksack).collect()
 }
 
-fn parse_rucksack(text: &str) -> Rucksack {
+fn parse_rucksack(line: Line) -> Result<Rucksack, ParseError> {
+    let text = line.text();
+    if let Some((i, item)) = text
+        .char_indices()
+        .find(|(_, item)| !item.is_ascii_alphabetic())
+    {
+        return Err(line.error(
+            &text[i..i + item.len_utf8()],
+            "an item (`a`-`z` or `A`-`Z`)",
+        ));
+    }
+    if !text.len().is_multiple_of(2) {
+        return Err(line.error_at_end("an even number of items"));
+    }
     let mid = text.len() / 2;
     let (first, second) = text.split_at(mid);
-    Rucksack {
-        all_items: text.to_string(),
-        first_compartment: first.to_string(),
-        second_compartment: second.to_string(),
-    }
+    Ok(Rucksack {
+        first_compartment: first.into(),
+        second_compartment: second.into(),
+    })
 }
 
 impl Rucksack {
-    fn find_packing_failure(&self) -> char {
-        for first_item in self.first_compartment.chars() {
-            if self.second_compartment.contains(first_item) {
-                return first_item;
-            }
-        }
-        panic!()
+    pub fn first_compartment(&self) -> ItemSet {
+        self.first_compartment
+    }
+
+    pub fn second_compartment(&self) -> ItemSet {
+        self.second_compartment
+    }
+
+    pub fn all_items(&self) -> ItemSet {
+        self.first_compartment.union(self.second_compartment)
+    }
+
+    /// Returns the items that are packed in both compartments.
+    pub fn packing_failures(&self) -> ItemSet {
+        self.first_compartment.intersection(self.second_compartment)
     }
 }
 
-fn calc_item_priority(item: char) -> u32 {
+/// Error for a character that isn't an item.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InvalidItem(pub char);
+
+impl fmt::Display for InvalidItem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "`{}` is not an item (`a`-`z` or `A`-`Z`)", self.0)
+    }
+}
+
+impl error::Error for InvalidItem {}
+
+fn item_priority(item: char) -> Option<u32> {
     match item {
-        'a'..='z' => item as u32 - 'a' as u32 + 1,
-        'A'..='Z' => item as u32 - 'A' as u32 + 27,
+        'a'..='z' => Some(item as u32 - 'a' as u32 + 1),
+        'A'..='Z' => Some(item as u32 - 'A' as u32 + 27),
+        _ => None,
+    }
+}
+
+fn calc_item_priority(item: char) -> u32 {
+    item_priority(item).unwrap_or_else(|| panic!("{}", InvalidItem(item)))
+}
+
+fn item_for_priority(priority: u32) -> char {
+    match priority {
+        1..=26 => char::from(b'a' + (priority - 1) as u8),
+        27..=52 => char::from(b'A' + (priority - 27) as u8),
         _ => panic!(),
     }
 }
 
+fn priority_sum(items: ItemSet) -> u32 {
+    items.items().map(calc_item_priority).sum()
+}
+
+/// Sums the priorities of the items that are packed in both compartments.
+///
+/// A rucksack without packing failures adds nothing and one with several failures adds all of
+/// them; use [`validate`] to find those rucksacks.
 pub fn challange1(rucksacks: &[Rucksack]) -> u32 {
     rucksacks
         .iter()
-        .map(Rucksack::find_packing_failure)
-        .map(calc_item_priority)
+        .map(Rucksack::packing_failures)
+        .map(priority_sum)
         .sum()
 }
 
-fn find_common_item(group: &[Rucksack]) -> char {
-    for item in group[0].all_items.chars() {
-        if group[1].all_items.contains(item) && group[2].all_items.contains(item) {
-            return item;
+/// Returns the items that are carried by every elf in the group.
+pub fn find_badges(group: &[Rucksack]) -> ItemSet {
+    group
+        .iter()
+        .map(Rucksack::all_items)
+        .reduce(ItemSet::intersection)
+        .unwrap_or_default()
+}
+
+/// Sums the priorities of the badges of each group of `group_size` elves.
+///
+/// Groups without a badge add nothing and groups with several badges add all of them; use
+/// [`validate`] to find those groups. Leftover elves at the end are ignored.
+///
+/// # Panics
+///
+/// Panics when `group_size` is zero.
+pub fn badge_priorities(rucksacks: &[Rucksack], group_size: usize) -> u32 {
+    assert!(group_size > 0, "group size must be at least one");
+    rucksacks
+        .chunks_exact(group_size)
+        .map(find_badges)
+        .map(priority_sum)
+        .sum()
+}
+
+pub fn challange2(rucksacks: &[Rucksack]) -> u32 {
+    badge_priorities(rucksacks, 3)
+}
+
+/// Something in the list of rucksacks that doesn't follow the puzzle rules.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Problem {
+    /// The rucksack doesn't have exactly one item in both compartments.
+    PackingFailures { rucksack: usize, items: ItemSet },
+    /// The group doesn't have exactly one item that all elves carry.
+    Badges { group: usize, items: ItemSet },
+    /// There are rucksacks left over that don't form a complete group.
+    IncompleteGroup { group: usize, rucksacks: usize },
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let describe = |items: &ItemSet| match items.len() {
+            0 => "no items".to_string(),
+            _ => format!("items {}", items.items().collect::<String>()),
+        };
+        match self {
+            Problem::PackingFailures { rucksack, items } => write!(
+                f,
+                "rucksack {} has {} in both compartments",
+                rucksack + 1,
+                describe(items)
+            ),
+            Problem::Badges { group, items } => {
+                write!(f, "group {} has {} in common", group + 1, describe(items))
+            }
+            Problem::IncompleteGroup { group, rucksacks } => {
+                write!(f, "group {} only has {} rucksacks", group + 1, rucksacks)
+            }
         }
     }
-    panic!()
 }
 
-pub fn challange2(rucksacks: &[Rucksack]) -> u32 {
-    let groups = rucksacks.chunks(3);
+/// Shared items of every rucksack and every group.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Validation {
+    /// Items in both compartments, per rucksack.
+    pub packing_failures: Vec<ItemSet>,
+    /// Items carried by all elves, per group. The last group may be incomplete.
+    pub badges: Vec<ItemSet>,
+    pub group_size: usize,
+    /// Number of rucksacks in the last group when it is incomplete.
+    pub leftover: usize,
+}
+
+impl Validation {
+    pub fn problems(&self) -> Vec<Problem> {
+        let mut problems: Vec<Problem> = self
+            .packing_failures
+            .iter()
+            .enumerate()
+            .filter(|(_, items)| items.len() != 1)
+            .map(|(rucksack, &items)| Problem::PackingFailures { rucksack, items })
+            .collect();
+        problems.extend(
+            self.badges
+                .iter()
+                .enumerate()
+                .filter(|(_, items)| items.len() != 1)
+                .map(|(group, &items)| Problem::Badges { group, items }),
+        );
+        if self.leftover > 0 {
+            problems.push(Problem::IncompleteGroup {
+                group: self.badges.len() - 1,
+                rucksacks: self.leftover,
+            });
+        }
+        problems
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.problems().is_empty()
+    }
+}
 
-    groups.map(find_common_item).map(calc_item_priority).sum()
+/// Collects the shared items of every rucksack and every group of `group_size` elves.
+///
+/// # Panics
+///
+/// Panics when `group_size` is zero.
+pub fn validate(rucksacks: &[Rucksack], group_size: usize) -> Validation {
+    assert!(group_size > 0, "group size must be at least one");
+    Validation {
+        packing_failures: rucksacks.iter().map(Rucksack::packing_failures).collect(),
+        badges: rucksacks.chunks(group_size).map(find_badges).collect(),
+        group_size,
+        leftover: rucksacks.len() % group_size,
+    }
+}
+
+/// Fails with the problems of the rucksacks that are relevant to a challange.
+fn check_problems(
+    rucksacks: &[Rucksack],
+    relevant: impl Fn(&Problem) -> bool,
+) -> Result<(), SolveError> {
+    let problems = validate(rucksacks, 3)
+        .problems()
+        .iter()
+        .filter(|problem| relevant(problem))
+        .map(Problem::to_string)
+        .collect::<Vec<_>>();
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(SolveError::new(problems.join(", ")))
+    }
+}
+
+pub struct Day03;
+
+impl Solution for Day03 {
+    const DAY: u8 = 3;
+
+    type Input = Vec<Rucksack>;
+
+    fn parse(text: &str) -> Result<Self::Input, ParseError> {
+        parse(text)
+    }
+
+    fn challange1(input: &Self::Input) -> Result<Answer, SolveError> {
+        check_problems(input, |problem| {
+            matches!(problem, Problem::PackingFailures { .. })
+        })?;
+        Ok(challange1(input).into())
+    }
+
+    fn challange2(input: &Self::Input) -> Result<Answer, SolveError> {
+        check_problems(input, |problem| {
+            !matches!(problem, Problem::PackingFailures { .. })
+        })?;
+        Ok(challange2(input).into())
+    }
 }
 
 #[cfg(test)]
@@ -92,54 +387,91 @@ CrZsJsPPZsGzwwsLwLmpwMDw";
 
     #[test]
     fn parse_example() {
-        let rucksacks = parse(EXAMPLE_TEXT);
+        let rucksacks = parse(EXAMPLE_TEXT).unwrap();
         assert_eq!(
             rucksacks,
             vec!(
                 Rucksack {
-                    all_items: "vJrwpWtwJgWrhcsFMMfFFhFp".to_string(),
-                    first_compartment: "vJrwpWtwJgWr".to_string(),
-                    second_compartment: "hcsFMMfFFhFp".to_string(),
+                    first_compartment: "vJrwpWtwJgWr".into(),
+                    second_compartment: "hcsFMMfFFhFp".into(),
                 },
                 Rucksack {
-                    all_items: "jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL".to_string(),
-                    first_compartment: "jqHRNqRjqzjGDLGL".to_string(),
-                    second_compartment: "rsFMfFZSrLrFZsSL".to_string(),
+                    first_compartment: "jqHRNqRjqzjGDLGL".into(),
+                    second_compartment: "rsFMfFZSrLrFZsSL".into(),
                 },
                 Rucksack {
-                    all_items: "PmmdzqPrVvPwwTWBwg".to_string(),
-                    first_compartment: "PmmdzqPrV".to_string(),
-                    second_compartment: "vPwwTWBwg".to_string(),
+                    first_compartment: "PmmdzqPrV".into(),
+                    second_compartment: "vPwwTWBwg".into(),
                 },
                 Rucksack {
-                    all_items: "wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn".to_string(),
-                    first_compartment: "wMqvLMZHhHMvwLH".to_string(),
-                    second_compartment: "jbvcjnnSBnvTQFn".to_string(),
+                    first_compartment: "wMqvLMZHhHMvwLH".into(),
+                    second_compartment: "jbvcjnnSBnvTQFn".into(),
                 },
                 Rucksack {
-                    all_items: "ttgJtRGJQctTZtZT".to_string(),
-                    first_compartment: "ttgJtRGJ".to_string(),
-                    second_compartment: "QctTZtZT".to_string(),
+                    first_compartment: "ttgJtRGJ".into(),
+                    second_compartment: "QctTZtZT".into(),
                 },
                 Rucksack {
-                    all_items: "CrZsJsPPZsGzwwsLwLmpwMDw".to_string(),
-                    first_compartment: "CrZsJsPPZsGz".to_string(),
-                    second_compartment: "wwsLwLmpwMDw".to_string(),
+                    first_compartment: "CrZsJsPPZsGz".into(),
+                    second_compartment: "wwsLwLmpwMDw".into(),
                 },
             )
         );
     }
 
+    #[test]
+    fn parse_invalid() {
+        let error = parse("abcA\nab-d").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 2, column 3: expected an item (`a`-`z` or `A`-`Z`), found `-`"
+        );
+        let error = parse("abc").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 1, column 4: expected an even number of items"
+        );
+    }
+
     #[test]
     fn packing_failure_example() {
-        let data = parse(EXAMPLE_TEXT);
-        let mut failures = data.iter().map(Rucksack::find_packing_failure);
-        assert_eq!(failures.next(), Some('p'));
-        assert_eq!(failures.next(), Some('L'));
-        assert_eq!(failures.next(), Some('P'));
-        assert_eq!(failures.next(), Some('v'));
-        assert_eq!(failures.next(), Some('t'));
-        assert_eq!(failures.next(), Some('s'));
+        let data = parse(EXAMPLE_TEXT).unwrap();
+        let mut failures = data.iter().map(Rucksack::packing_failures);
+        assert_eq!(failures.next(), Some("p".into()));
+        assert_eq!(failures.next(), Some("L".into()));
+        assert_eq!(failures.next(), Some("P".into()));
+        assert_eq!(failures.next(), Some("v".into()));
+        assert_eq!(failures.next(), Some("t".into()));
+        assert_eq!(failures.next(), Some("s".into()));
+    }
+
+    #[test]
+    fn item_set() {
+        let first = ItemSet::from("vJrwpWtwJgWr");
+        let second = ItemSet::from("hcsFMMfFFhFp");
+        assert_eq!(first.len(), 8);
+        assert!(first.contains('J'));
+        assert!(!first.contains('F'));
+        assert_eq!(first.intersection(second), ItemSet::from("p"));
+        assert_eq!(
+            first.union(second).items().collect::<String>(),
+            "cfghprstvwFJMW"
+        );
+        assert_eq!(first.first(), Some('g'));
+        assert_eq!(ItemSet::new().first(), None);
+        assert_eq!(ItemSet::try_from_items("vJrwpWtwJgWr"), Ok(first));
+        let error = ItemSet::try_from_items("ab-d").unwrap_err();
+        assert_eq!(error, InvalidItem('-'));
+        assert_eq!(error.to_string(), "`-` is not an item (`a`-`z` or `A`-`Z`)");
+        assert!(!first.contains('-'));
+        assert!(ItemSet::new().is_empty());
+    }
+
+    #[test]
+    fn item_for_priority_example() {
+        for item in ('a'..='z').chain('A'..='Z') {
+            assert_eq!(item_for_priority(calc_item_priority(item)), item);
+        }
     }
 
     #[test]
@@ -154,13 +486,101 @@ CrZsJsPPZsGzwwsLwLmpwMDw";
 
     #[test]
     fn challange1_example() {
-        let data = parse(EXAMPLE_TEXT);
+        let data = parse(EXAMPLE_TEXT).unwrap();
         assert_eq!(challange1(&data), 157);
     }
 
     #[test]
     fn challange2_example() {
-        let data = parse(EXAMPLE_TEXT);
+        let data = parse(EXAMPLE_TEXT).unwrap();
         assert_eq!(challange2(&data), 70);
     }
+
+    #[test]
+    fn badge_priorities_example() {
+        let data = parse(EXAMPLE_TEXT).unwrap();
+        assert_eq!(badge_priorities(&data, 3), 70);
+        assert_eq!(find_badges(&data[0..2]), "frsFM".into());
+        assert_eq!(badge_priorities(&data, 6), 0);
+        assert_eq!(badge_priorities(&data, 4), 0);
+        assert_eq!(badge_priorities(&data[0..1], 3), 0);
+    }
+
+    #[test]
+    fn validate_example() {
+        let data = parse(EXAMPLE_TEXT).unwrap();
+        let validation = validate(&data, 3);
+        assert!(validation.is_valid());
+        assert_eq!(validation.badges, vec!["r".into(), "Z".into()]);
+
+        let validation = validate(&data, 4);
+        assert_eq!(validation.badges, vec![ItemSet::new(), "GJZ".into()]);
+        assert_eq!(
+            validation
+                .problems()
+                .iter()
+                .map(Problem::to_string)
+                .collect::<Vec<_>>(),
+            vec![
+                "group 1 has no items in common",
+                "group 2 has items GJZ in common",
+                "group 2 only has 2 rucksacks"
+            ]
+        );
+    }
+
+    #[test]
+    fn validate_invalid() {
+        let data = parse("abcd\nabab\nxyzw").unwrap();
+        let problems = validate(&data, 3).problems();
+        assert_eq!(
+            problems,
+            vec![
+                Problem::PackingFailures {
+                    rucksack: 0,
+                    items: ItemSet::new()
+                },
+                Problem::PackingFailures {
+                    rucksack: 1,
+                    items: "ab".into()
+                },
+                Problem::PackingFailures {
+                    rucksack: 2,
+                    items: ItemSet::new()
+                },
+                Problem::Badges {
+                    group: 0,
+                    items: ItemSet::new()
+                },
+            ]
+        );
+        assert_eq!(
+            problems[0].to_string(),
+            "rucksack 1 has no items in both compartments"
+        );
+        assert_eq!(challange1(&data), 3);
+        assert_eq!(challange2(&data), 0);
+    }
+
+    #[test]
+    fn solution_reports_problems() {
+        let data = parse(EXAMPLE_TEXT).unwrap();
+        assert_eq!(Day03::challange1(&data).unwrap(), Answer::Integer(157));
+        assert_eq!(Day03::challange2(&data).unwrap(), Answer::Integer(70));
+
+        let data = parse("abab\nabab\nabab\nxyxy").unwrap();
+        assert_eq!(
+            Day03::challange1(&data).unwrap_err().to_string(),
+            "rucksack 1 has items ab in both compartments, \
+             rucksack 2 has items ab in both compartments, \
+             rucksack 3 has items ab in both compartments, \
+             rucksack 4 has items xy in both compartments"
+        );
+        assert_eq!(
+            Day03::challange2(&data).unwrap_err().to_string(),
+            "group 1 has items ab in common, \
+             group 2 has items xy in common, \
+             group 2 only has 1 rucksacks"
+        );
+    }
 }
@@ -15,43 +15,199 @@
  * along with this program.  If not, see <http://www.gnu.org/licenses/>.
  */
 
-use std::ops::RangeInclusive;
+use crate::{
+    interval::{Interval, IntervalSet},
+    parse::{lines, Line, ParseError},
+    solution::{Answer, Solution, SolveError},
+};
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Assignment {
-    first: RangeInclusive<u32>,
-    second: RangeInclusive<u32>,
+    first: Interval,
+    second: Interval,
 }
 
-pub fn parse(text: &str) -> Vec<Assignment> {
-    text.lines().map(parse_assignment).collect()
+pub fn parse(text: &str) -> Result<Vec<Assignment>, ParseError> {
+    lines(text).map(parse_assignment).collect()
 }
 
-fn parse_assignment(text: &str) -> Assignment {
-    let (first_range, second_range) = text.split_once(',').unwrap();
-    Assignment {
-        first: parse_range(first_range),
-        second: parse_range(second_range),
-    }
+fn parse_assignment(line: Line) -> Result<Assignment, ParseError> {
+    let (first_range, second_range) = line.split_once(line.text(), ',')?;
+    Ok(Assignment {
+        first: parse_range(&line, first_range)?,
+        second: parse_range(&line, second_range)?,
+    })
 }
 
-fn parse_range(text: &str) -> RangeInclusive<u32> {
-    let (first, second) = text.split_once('-').unwrap();
+fn parse_range(line: &Line, text: &str) -> Result<Interval, ParseError> {
+    let (first, second) = line.split_once(text, '-')?;
 
-    RangeInclusive::new(first.parse().unwrap(), second.parse().unwrap())
+    let start = line.parse(first, "a section number")?;
+    let end = line.parse(second, "a section number")?;
+    if end < start {
+        return Err(line.error(second, format!("a section number of at least {start}")));
+    }
+    Ok(Interval::new(start, end))
 }
 
 impl Assignment {
+    /// Returns the sections of both elves.
+    pub fn elves(&self) -> [Interval; 2] {
+        [self.first, self.second]
+    }
+
+    /// Returns the sections that both elves have to clean.
+    pub fn overlap(&self) -> Option<Interval> {
+        self.first.intersection(&self.second)
+    }
+
     fn has_fully_overlap(&self) -> bool {
-        (self.first.contains(self.second.start()) && self.first.contains(self.second.end()))
-            || (self.second.contains(self.first.start()) && self.second.contains(self.first.end()))
+        self.first.contains_interval(&self.second) || self.second.contains_interval(&self.first)
     }
 
     fn has_any_overlap(&self) -> bool {
-        self.first.contains(self.second.start())
-            || self.first.contains(self.second.end())
-            || self.second.contains(self.first.start())
-            || self.second.contains(self.first.end())
+        self.first.overlaps(&self.second)
+    }
+}
+
+/// Returns the sections that at least one elf has to clean.
+pub fn covered_sections(assignments: &[Assignment]) -> IntervalSet {
+    assignments.iter().flat_map(Assignment::elves).collect()
+}
+
+/// Returns the sections between the lowest and highest assigned section that nobody cleans.
+pub fn uncovered_sections(assignments: &[Assignment]) -> IntervalSet {
+    let covered = covered_sections(assignments);
+    match covered.span() {
+        Some(span) => covered.gaps(span),
+        None => IntervalSet::new(),
+    }
+}
+
+/// Returns the sections that both elves of the same pair have to clean.
+pub fn double_sections(assignments: &[Assignment]) -> IntervalSet {
+    assignments.iter().filter_map(Assignment::overlap).collect()
+}
+
+/// Sections that are cleaned by the same number of elves.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Coverage {
+    pub sections: Interval,
+    pub elves: usize,
+}
+
+/// An elf whose sections are all cleaned by another elf as well.
+///
+/// Elves are numbered in input order, two per line. When several elves have the same sections,
+/// the first one is kept and the others are redundant.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Redundancy {
+    pub elf: usize,
+    pub covered_by: usize,
+}
+
+/// Result of comparing all elves of all assignments with each other.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Analysis {
+    /// Number of elves per section, from the lowest to the highest assigned section.
+    pub coverage: Vec<Coverage>,
+    /// Redundant elves, ordered by elf.
+    pub redundant: Vec<Redundancy>,
+}
+
+impl Analysis {
+    /// Number of elves that clean `section`.
+    pub fn elves_at(&self, section: u32) -> usize {
+        let index = self
+            .coverage
+            .partition_point(|coverage| coverage.sections.end() < section);
+        self.coverage
+            .get(index)
+            .filter(|coverage| coverage.sections.contains(section))
+            .map_or(0, |coverage| coverage.elves)
+    }
+
+    /// Largest number of elves that clean the same section.
+    pub fn max_concurrency(&self) -> usize {
+        self.coverage
+            .iter()
+            .map(|coverage| coverage.elves)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Sections that are cleaned by the largest number of elves.
+    pub fn busiest_sections(&self) -> IntervalSet {
+        let max = self.max_concurrency();
+        self.coverage
+            .iter()
+            .filter(|coverage| coverage.elves == max)
+            .map(|coverage| coverage.sections)
+            .collect()
+    }
+
+    /// Sections that are cleaned by exactly `elves` elves.
+    pub fn sections_with(&self, elves: usize) -> IntervalSet {
+        self.coverage
+            .iter()
+            .filter(|coverage| coverage.elves == elves)
+            .map(|coverage| coverage.sections)
+            .collect()
+    }
+}
+
+/// Compares the sections of all elves using a sweep line, in `O(n log n)` for `n` elves.
+pub fn analyze(assignments: &[Assignment]) -> Analysis {
+    let elves: Vec<Interval> = assignments.iter().flat_map(Assignment::elves).collect();
+
+    // Every elf adds one at its first section and removes one just after its last section.
+    let mut events: Vec<(u64, isize)> = elves
+        .iter()
+        .flat_map(|elf| [(u64::from(elf.start()), 1), (u64::from(elf.end()) + 1, -1)])
+        .collect();
+    events.sort_unstable();
+
+    let mut coverage = Vec::new();
+    let mut count = 0;
+    for (i, &(position, change)) in events.iter().enumerate() {
+        count += change;
+        let next = match events.get(i + 1) {
+            Some(&(next, _)) if next > position => next,
+            _ => continue,
+        };
+        let sections = Interval::new(position as u32, (next - 1) as u32);
+        let elves = count as usize;
+        match coverage.last_mut() {
+            Some(Coverage {
+                sections: last,
+                elves: last_elves,
+            }) if *last_elves == elves => *last = last.union(&sections).unwrap(),
+            _ => coverage.push(Coverage { sections, elves }),
+        }
+    }
+
+    // After sorting on start, and on descending end for equal starts, an elf is contained in
+    // another exactly when an earlier elf reaches at least as far.
+    let mut order: Vec<usize> = (0..elves.len()).collect();
+    order.sort_unstable_by_key(|&elf| {
+        (elves[elf].start(), std::cmp::Reverse(elves[elf].end()), elf)
+    });
+    let mut redundant = Vec::new();
+    let mut furthest: Option<usize> = None;
+    for elf in order {
+        match furthest {
+            Some(other) if elves[elf].end() <= elves[other].end() => redundant.push(Redundancy {
+                elf,
+                covered_by: other,
+            }),
+            _ => furthest = Some(elf),
+        }
+    }
+    redundant.sort_unstable_by_key(|redundancy| redundancy.elf);
+
+    Analysis {
+        coverage,
+        redundant,
     }
 }
 
@@ -73,6 +229,26 @@ pub fn challange2(assignments: &[Assignment]) -> u32 {
         .unwrap()
 }
 
+pub struct Day04;
+
+impl Solution for Day04 {
+    const DAY: u8 = 4;
+
+    type Input = Vec<Assignment>;
+
+    fn parse(text: &str) -> Result<Self::Input, ParseError> {
+        parse(text)
+    }
+
+    fn challange1(input: &Self::Input) -> Result<Answer, SolveError> {
+        Ok(challange1(input).into())
+    }
+
+    fn challange2(input: &Self::Input) -> Result<Answer, SolveError> {
+        Ok(challange2(input).into())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -88,59 +264,167 @@ mod tests {
 
     #[test]
     fn parse_example() {
-        let assignments = parse(EXAMPLE_TEXT);
+        let assignments = parse(EXAMPLE_TEXT).unwrap();
         assert_eq!(
             assignments,
             vec!(
                 Assignment {
-                    first: 2..=4,
-                    second: 6..=8,
+                    first: Interval::new(2, 4),
+                    second: Interval::new(6, 8),
                 },
                 Assignment {
-                    first: 2..=3,
-                    second: 4..=5,
+                    first: Interval::new(2, 3),
+                    second: Interval::new(4, 5),
                 },
                 Assignment {
-                    first: 5..=7,
-                    second: 7..=9,
+                    first: Interval::new(5, 7),
+                    second: Interval::new(7, 9),
                 },
                 Assignment {
-                    first: 2..=8,
-                    second: 3..=7,
+                    first: Interval::new(2, 8),
+                    second: Interval::new(3, 7),
                 },
                 Assignment {
-                    first: 6..=6,
-                    second: 4..=6,
+                    first: Interval::new(6, 6),
+                    second: Interval::new(4, 6),
                 },
                 Assignment {
-                    first: 2..=6,
-                    second: 4..=8,
+                    first: Interval::new(2, 6),
+                    second: Interval::new(4, 8),
                 },
             )
         );
     }
 
+    #[test]
+    fn parse_invalid() {
+        let error = parse("2-4,6-8\n2-3;4-5").unwrap_err();
+        assert_eq!(error.to_string(), "line 2, column 8: expected `,`");
+        let error = parse("2-4,6-x").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 1, column 7: expected a section number, found `x`"
+        );
+        let error = parse("2-4,6-5").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 1, column 7: expected a section number of at least 6, found `5`"
+        );
+    }
+
     #[test]
     fn has_fully_overlap_example() {
-        let assignments = parse(EXAMPLE_TEXT);
+        let assignments = parse(EXAMPLE_TEXT).unwrap();
         let mut fully_overlap = assignments.iter().map(Assignment::has_fully_overlap);
-        assert_eq!(fully_overlap.next().unwrap(), false);
-        assert_eq!(fully_overlap.next().unwrap(), false);
-        assert_eq!(fully_overlap.next().unwrap(), false);
-        assert_eq!(fully_overlap.next().unwrap(), true);
-        assert_eq!(fully_overlap.next().unwrap(), true);
-        assert_eq!(fully_overlap.next().unwrap(), false);
+        assert!(!fully_overlap.next().unwrap());
+        assert!(!fully_overlap.next().unwrap());
+        assert!(!fully_overlap.next().unwrap());
+        assert!(fully_overlap.next().unwrap());
+        assert!(fully_overlap.next().unwrap());
+        assert!(!fully_overlap.next().unwrap());
+    }
+
+    #[test]
+    fn overlap_example() {
+        let assignments = parse(EXAMPLE_TEXT).unwrap();
+        let overlaps: Vec<_> = assignments.iter().map(Assignment::overlap).collect();
+        assert_eq!(
+            overlaps,
+            vec![
+                None,
+                None,
+                Some(Interval::new(7, 7)),
+                Some(Interval::new(3, 7)),
+                Some(Interval::new(6, 6)),
+                Some(Interval::new(4, 6)),
+            ]
+        );
+    }
+
+    #[test]
+    fn sections_example() {
+        let assignments = parse(EXAMPLE_TEXT).unwrap();
+        assert_eq!(covered_sections(&assignments).to_string(), "2-9");
+        assert!(uncovered_sections(&assignments).is_empty());
+        assert_eq!(double_sections(&assignments).to_string(), "3-7");
+        assert_eq!(double_sections(&assignments).len(), 5);
+
+        let assignments = parse("1-2,8-9\n3-3,7-7").unwrap();
+        assert_eq!(uncovered_sections(&assignments).to_string(), "4-6");
+        assert!(double_sections(&assignments).is_empty());
+        assert!(uncovered_sections(&[]).is_empty());
+    }
+
+    #[test]
+    fn analyze_example() {
+        let assignments = parse(EXAMPLE_TEXT).unwrap();
+        let analysis = analyze(&assignments);
+        let counts: Vec<usize> = (1..=10).map(|section| analysis.elves_at(section)).collect();
+        assert_eq!(counts, vec![0, 4, 5, 7, 7, 8, 6, 4, 1, 0]);
+        assert_eq!(analysis.max_concurrency(), 8);
+        assert_eq!(analysis.busiest_sections().to_string(), "6-6");
+        assert_eq!(analysis.sections_with(4).to_string(), "2-2,8-8");
+        assert_eq!(
+            analysis.coverage.first(),
+            Some(&Coverage {
+                sections: Interval::new(2, 2),
+                elves: 4
+            })
+        );
+        assert_eq!(
+            analysis
+                .redundant
+                .iter()
+                .map(|redundancy| (redundancy.elf, redundancy.covered_by))
+                .collect::<Vec<_>>(),
+            vec![
+                (0, 6),
+                (1, 6),
+                (2, 6),
+                (3, 6),
+                (4, 6),
+                (7, 6),
+                (8, 6),
+                (9, 6),
+                (10, 6),
+                (11, 6)
+            ]
+        );
+    }
+
+    #[test]
+    fn analyze_duplicates() {
+        let assignments = parse("3-5,1-2\n3-5,8-9\n2-2,4294967290-4294967295").unwrap();
+        let analysis = analyze(&assignments);
+        assert_eq!(
+            analysis.redundant,
+            vec![
+                Redundancy {
+                    elf: 2,
+                    covered_by: 0
+                },
+                Redundancy {
+                    elf: 4,
+                    covered_by: 1
+                },
+            ]
+        );
+        assert_eq!(analysis.sections_with(0).to_string(), "6-7,10-4294967289");
+        assert_eq!(analysis.sections_with(2).to_string(), "2-5");
+        assert_eq!(analysis.elves_at(u32::MAX), 1);
+        assert_eq!(analysis.elves_at(0), 0);
+        assert_eq!(analyze(&[]).max_concurrency(), 0);
     }
 
     #[test]
     fn challange1_example() {
-        let data = parse(EXAMPLE_TEXT);
+        let data = parse(EXAMPLE_TEXT).unwrap();
         assert_eq!(challange1(&data), 2);
     }
 
     #[test]
     fn challange2_example() {
-        let data = parse(EXAMPLE_TEXT);
+        let data = parse(EXAMPLE_TEXT).unwrap();
         assert_eq!(challange2(&data), 4);
     }
 }
@@ -15,29 +15,204 @@
  * along with this program.  If not, see <http://www.gnu.org/licenses/>.
  */
 
-use std::collections::VecDeque;
+use crate::{
+    parse::{lines, Line, ParseError, Words},
+    solution::{Answer, Solution, SolveError},
+};
+use std::{collections::VecDeque, error, fmt, ops::Range};
 
+/// The stacks of crates on the ship, numbered from 1. The front of each stack is the top crate.
 #[derive(Clone, Debug, PartialEq, Eq, Default)]
 pub struct Ship {
-    layout: [VecDeque<char>; 9],
+    stacks: Vec<VecDeque<String>>,
 }
 
 impl Ship {
-    fn execute_challange1(&mut self, instruction: Instruction) {
-        for _ in 0..instruction.amount {
-            let crane = self.layout[instruction.from - 1].pop_front().unwrap();
-            self.layout[instruction.to - 1].push_front(crane)
+    /// Creates a ship from stacks that are listed from the top crate to the bottom crate.
+    pub fn new<S: Into<String>>(stacks: impl IntoIterator<Item = Vec<S>>) -> Self {
+        Self {
+            stacks: stacks
+                .into_iter()
+                .map(|stack| stack.into_iter().map(Into::into).collect())
+                .collect(),
         }
     }
 
-    fn execute_challange2(&mut self, instruction: Instruction) {
-        let mut crane = VecDeque::new();
-        for _ in 0..instruction.amount {
-            crane.push_front(self.layout[instruction.from - 1].pop_front().unwrap());
+    pub fn stack_count(&self) -> usize {
+        self.stacks.len()
+    }
+
+    /// Returns the crates of a stack, from top to bottom, or `None` for a missing stack.
+    pub fn stack(&self, number: usize) -> Option<&VecDeque<String>> {
+        self.stacks.get(number.checked_sub(1)?)
+    }
+
+    /// Returns the top crate of every stack, or `None` for an empty stack.
+    pub fn top_crates(&self) -> Vec<Option<&str>> {
+        self.stacks
+            .iter()
+            .map(|stack| stack.front().map(String::as_str))
+            .collect()
+    }
+
+    /// Returns the labels of the top crates, skipping empty stacks.
+    pub fn top_crate_labels(&self) -> String {
+        self.top_crates().into_iter().flatten().collect()
+    }
+
+    /// Executes an instruction with `crane`. The ship is left unchanged when this fails.
+    pub fn execute(
+        &mut self,
+        crane: &dyn Crane,
+        instruction: &Instruction,
+    ) -> Result<(), MoveError> {
+        let available = self.checked_stack(instruction.from)?.len();
+        self.checked_stack(instruction.to)?;
+        if available < instruction.amount {
+            return Err(MoveError::NotEnoughCrates {
+                stack: instruction.from,
+                requested: instruction.amount,
+                available,
+            });
         }
-        for _ in 0..instruction.amount {
-            self.layout[instruction.to - 1].push_front(crane.pop_front().unwrap());
+
+        let capacity = crane.capacity();
+        assert!(capacity > 0, "a crane must lift at least one crate");
+        let mut remaining = instruction.amount;
+        while remaining > 0 {
+            let lift = remaining.min(capacity);
+            let crates: Vec<String> = self.stacks[instruction.from - 1].drain(..lift).collect();
+            let to = &mut self.stacks[instruction.to - 1];
+            for item in crates.into_iter().rev() {
+                to.push_front(item);
+            }
+            remaining -= lift;
         }
+        Ok(())
+    }
+
+    fn checked_stack(&self, number: usize) -> Result<&VecDeque<String>, MoveError> {
+        self.stack(number)
+            .ok_or(MoveError::MissingStack { stack: number })
+    }
+}
+
+/// Draws the ship in the same format as the puzzle input, including the numbered footer.
+///
+/// Every column is wide enough for its widest crate and stack number, and every row is padded to
+/// the full width of the drawing.
+impl fmt::Display for Ship {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let widths: Vec<usize> = self
+            .stacks
+            .iter()
+            .enumerate()
+            .map(|(i, stack)| {
+                let label = stack.iter().map(|label| label.chars().count()).max();
+                let number = (i + 1).to_string().len() + 2;
+                number.max(label.unwrap_or(1) + 2)
+            })
+            .collect();
+        let height = self.stacks.iter().map(VecDeque::len).max().unwrap_or(0);
+        for row in 0..height {
+            for (i, (stack, width)) in self.stacks.iter().zip(&widths).enumerate() {
+                if i > 0 {
+                    write!(f, " ")?;
+                }
+                // Stacks are aligned at the bottom, so shorter stacks start lower.
+                let cell = (row + stack.len())
+                    .checked_sub(height)
+                    .map(|depth| format!("[{}]", stack[depth]))
+                    .unwrap_or_default();
+                write!(f, "{cell:<width$}")?;
+            }
+            writeln!(f)?;
+        }
+        for (i, width) in widths.iter().enumerate() {
+            if i > 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{:^width$}", i + 1)?;
+        }
+        Ok(())
+    }
+}
+
+/// Error returned when an instruction can't be executed on a ship.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MoveError {
+    MissingStack {
+        stack: usize,
+    },
+    NotEnoughCrates {
+        stack: usize,
+        requested: usize,
+        available: usize,
+    },
+}
+
+impl fmt::Display for MoveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MoveError::MissingStack { stack } => write!(f, "stack {stack} doesn't exist"),
+            MoveError::NotEnoughCrates {
+                stack,
+                requested,
+                available,
+            } => write!(
+                f,
+                "cannot move {requested} crates from stack {stack}, which has {available}"
+            ),
+        }
+    }
+}
+
+impl error::Error for MoveError {}
+
+/// A crane that moves crates between stacks.
+pub trait Crane {
+    /// Largest number of crates that are lifted at once. Crates that are lifted together keep
+    /// their order.
+    fn capacity(&self) -> usize;
+}
+
+/// Moves one crate at a time, which reverses the order of the moved crates.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CrateMover9000;
+
+impl Crane for CrateMover9000 {
+    fn capacity(&self) -> usize {
+        1
+    }
+}
+
+/// Moves all crates at once, which keeps their order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CrateMover9001;
+
+impl Crane for CrateMover9001 {
+    fn capacity(&self) -> usize {
+        usize::MAX
+    }
+}
+
+/// Moves at most a fixed number of crates per lift.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CapacityLimited(usize);
+
+impl CapacityLimited {
+    /// # Panics
+    ///
+    /// Panics when `capacity` is zero.
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "a crane must lift at least one crate");
+        Self(capacity)
+    }
+}
+
+impl Crane for CapacityLimited {
+    fn capacity(&self) -> usize {
+        self.0
     }
 }
 
@@ -48,68 +223,348 @@ pub struct Instruction {
     to: usize,
 }
 
-pub fn parse(text: &str) -> (Ship, Vec<Instruction>) {
-    let (ship_text, instruction_text) = text.split_once("\n\n").unwrap();
+impl Instruction {
+    pub fn new(amount: usize, from: usize, to: usize) -> Self {
+        Self { amount, from, to }
+    }
+
+    pub fn amount(&self) -> usize {
+        self.amount
+    }
 
-    (parse_ship(ship_text), parse_instructions(instruction_text))
+    pub fn from(&self) -> usize {
+        self.from
+    }
+
+    pub fn to(&self) -> usize {
+        self.to
+    }
 }
 
-fn parse_ship(text: &str) -> Ship {
-    let mut ship = Ship::default();
-    for line in text.lines() {
-        let container_row =
-            line.chars()
-                .enumerate()
-                .filter_map(|(i, c)| if i % 4 == 1 { Some(c) } else { None });
-        for (position, container) in container_row.enumerate() {
-            if container.is_alphabetic() {
-                ship.layout[position].push_back(container)
-            }
-        }
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "move {} from {} to {}", self.amount, self.from, self.to)
     }
-    ship
 }
 
-fn parse_instructions(text: &str) -> Vec<Instruction> {
-    text.lines().map(parse_instruction).collect()
+/// Writes a ship and instructions as a puzzle input, which [`parse`] reads back.
+pub fn render(ship: &Ship, instructions: &[Instruction]) -> String {
+    let mut text = format!("{ship}\n\n");
+    for instruction in instructions {
+        text += &format!("{instruction}\n");
+    }
+    text
 }
 
-fn parse_instruction(text: &str) -> Instruction {
-    let mut parts = text.split_whitespace();
-    assert_eq!(parts.next().unwrap(), "move");
-    let amount = parts.next().unwrap().parse().unwrap();
-    assert_eq!(parts.next().unwrap(), "from");
-    let from = parts.next().unwrap().parse().unwrap();
-    assert_eq!(parts.next().unwrap(), "to");
-    let to = parts.next().unwrap().parse().unwrap();
-    Instruction { amount, from, to }
+/// Executes all instructions with `crane`, stopping at the first instruction that fails.
+///
+/// The error contains the index of the failing instruction.
+pub fn run(
+    ship: &mut Ship,
+    crane: &dyn Crane,
+    instructions: &[Instruction],
+) -> Result<(), (usize, MoveError)> {
+    for (i, instruction) in instructions.iter().enumerate() {
+        ship.execute(crane, instruction)
+            .map_err(|error| (i, error))?;
+    }
+    Ok(())
 }
 
-pub fn challange1((ship, instructions): (Ship, Vec<Instruction>)) -> String {
-    let mut ship = ship;
-    for instruction in instructions {
-        ship.execute_challange1(instruction);
+pub fn parse(text: &str) -> Result<(Ship, Vec<Instruction>), ParseError> {
+    let lines = lines(text).collect::<Vec<_>>();
+    let separator = lines
+        .iter()
+        .position(|line| line.text().is_empty())
+        .ok_or_else(|| ParseError::end_of_input(text, "an empty line after the drawing"))?;
+    let (ship_lines, instruction_lines) = lines.split_at(separator);
+
+    let ship = parse_ship(ship_lines)?;
+    let instructions = parse_instructions(&instruction_lines[1..], ship.stack_count())?;
+    Ok((ship, instructions))
+}
+
+/// Splits a line into its words, together with the character columns each word spans.
+fn columns<'a>(line: &Line<'a>) -> impl Iterator<Item = (&'a str, Range<usize>)> {
+    let text = line.text();
+    let mut chars = text.char_indices().enumerate().peekable();
+    std::iter::from_fn(move || {
+        while chars.next_if(|(_, (_, c))| c.is_whitespace()).is_some() {}
+        let (start_column, (start, c)) = chars.next()?;
+        let mut end_column = start_column + 1;
+        let mut end = start + c.len_utf8();
+        while let Some((column, (i, c))) = chars.next_if(|(_, (_, c))| !c.is_whitespace()) {
+            end_column = column + 1;
+            end = i + c.len_utf8();
+        }
+        Some((&text[start..end], start_column..end_column))
+    })
+}
+
+/// Reads the numbered footer and returns the columns of each stack number.
+fn parse_footer(line: &Line) -> Result<Vec<Range<usize>>, ParseError> {
+    let mut stacks = Vec::new();
+    for (word, span) in columns(line) {
+        let expected = format!("stack number {}", stacks.len() + 1);
+        if word.parse() != Ok(stacks.len() + 1) {
+            return Err(line.error(word, expected));
+        }
+        stacks.push(span);
+    }
+    if stacks.is_empty() {
+        return Err(line.error_at_end("stack number 1"));
     }
-    ship.layout
+    Ok(stacks)
+}
+
+fn parse_ship(lines: &[Line]) -> Result<Ship, ParseError> {
+    let (footer, drawing) = lines
+        .split_last()
+        .ok_or_else(|| ParseError::new(1, 1, "a drawing of the stacks"))?;
+    let numbers = parse_footer(footer)?;
+    let mut ship = Ship {
+        stacks: vec![VecDeque::new(); numbers.len()],
+    };
+    for line in drawing {
+        let text = line.text();
+        let mut chars = text.char_indices().enumerate();
+        let mut next_stack = 0;
+        while let Some((open_column, (open, c))) = chars.next() {
+            if c != '[' {
+                continue;
+            }
+            let (close_column, (close, _)) = chars
+                .find(|(_, (_, c))| *c == ']')
+                .ok_or_else(|| line.error_at_end("`]`"))?;
+            let label = &text[open + 1..close];
+            if label.is_empty() {
+                return Err(line.error(label, "a crate label"));
+            }
+            let stack = numbers
+                .iter()
+                .position(|number| number.start <= close_column && open_column < number.end)
+                .filter(|&stack| stack >= next_stack)
+                .ok_or_else(|| line.error(&text[open..=close], "a crate above a stack number"))?;
+            ship.stacks[stack].push_back(label.to_string());
+            next_stack = stack + 1;
+        }
+    }
+    Ok(ship)
+}
+
+fn parse_instructions(lines: &[Line], stack_count: usize) -> Result<Vec<Instruction>, ParseError> {
+    lines
         .iter()
-        .map(|vec| vec.front().unwrap_or(&' '))
+        .map(|line| parse_instruction(line, stack_count))
+        .collect()
+}
+
+fn parse_instruction(line: &Line, stack_count: usize) -> Result<Instruction, ParseError> {
+    let mut words = line.words();
+    let stack_number = |words: &mut Words| {
+        let word = words.next("a stack number")?;
+        let number = line.parse(word, "a stack number")?;
+        if !(1..=stack_count).contains(&number) {
+            return Err(line.error(word, format!("a stack number from 1 to {stack_count}")));
+        }
+        Ok(number)
+    };
+    words.keyword("move")?;
+    let amount = words.parse("an amount of crates")?;
+    words.keyword("from")?;
+    let from = stack_number(&mut words)?;
+    words.keyword("to")?;
+    let to = stack_number(&mut words)?;
+    words.end()?;
+    Ok(Instruction { amount, from, to })
+}
+
+/// A move that was executed by a [`Replay`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Step {
+    /// Index of the executed instruction.
+    pub instruction: usize,
+    /// The moved crates, from top to bottom as they were on the source stack.
+    pub crates: Vec<String>,
+    /// Top crate of every stack after the move.
+    pub top_crates: Vec<Option<String>>,
+}
+
+/// Executes instructions one at a time, while recording each move so it can be undone.
+pub struct Replay<'a> {
+    ship: Ship,
+    crane: &'a dyn Crane,
+    instructions: &'a [Instruction],
+    initial_top_crates: Vec<Option<String>>,
+    steps: Vec<Step>,
+}
+
+fn owned_top_crates(ship: &Ship) -> Vec<Option<String>> {
+    ship.top_crates()
+        .into_iter()
+        .map(|label| label.map(str::to_string))
         .collect()
 }
 
-pub fn challange2((ship, instructions): (Ship, Vec<Instruction>)) -> String {
+impl<'a> Replay<'a> {
+    pub fn new(ship: Ship, crane: &'a dyn Crane, instructions: &'a [Instruction]) -> Self {
+        Self {
+            initial_top_crates: owned_top_crates(&ship),
+            ship,
+            crane,
+            instructions,
+            steps: Vec::new(),
+        }
+    }
+
+    pub fn ship(&self) -> &Ship {
+        &self.ship
+    }
+
+    /// Number of instructions that are executed.
+    pub fn position(&self) -> usize {
+        self.steps.len()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.position() == self.instructions.len()
+    }
+
+    /// Returns the executed moves, in order.
+    pub fn steps(&self) -> &[Step] {
+        &self.steps
+    }
+
+    /// Returns the top crates before the first instruction and after every executed instruction.
+    pub fn top_crates_history(&self) -> Vec<&[Option<String>]> {
+        std::iter::once(self.initial_top_crates.as_slice())
+            .chain(self.steps.iter().map(|step| step.top_crates.as_slice()))
+            .collect()
+    }
+
+    /// Executes the next instruction. Returns `false` when all instructions are executed.
+    pub fn step_forward(&mut self) -> Result<bool, MoveError> {
+        let index = self.position();
+        let Some(instruction) = self.instructions.get(index) else {
+            return Ok(false);
+        };
+        let crates = self
+            .ship
+            .stack(instruction.from)
+            .map(|stack| stack.iter().take(instruction.amount).cloned().collect())
+            .unwrap_or_default();
+        self.ship.execute(self.crane, instruction)?;
+        self.steps.push(Step {
+            instruction: index,
+            crates,
+            top_crates: owned_top_crates(&self.ship),
+        });
+        Ok(true)
+    }
+
+    /// Undoes the last executed instruction. Returns `false` when nothing is executed.
+    pub fn step_back(&mut self) -> bool {
+        let Some(step) = self.steps.pop() else {
+            return false;
+        };
+        let instruction = &self.instructions[step.instruction];
+        let stacks = &mut self.ship.stacks;
+        stacks[instruction.to - 1].drain(..instruction.amount);
+        for label in step.crates.into_iter().rev() {
+            stacks[instruction.from - 1].push_front(label);
+        }
+        true
+    }
+
+    /// Moves forward or backward until `position` instructions are executed.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `position` is larger than the number of instructions.
+    pub fn jump_to(&mut self, position: usize) -> Result<(), (usize, MoveError)> {
+        assert!(
+            position <= self.instructions.len(),
+            "there are only {} instructions",
+            self.instructions.len()
+        );
+        while self.position() > position {
+            self.step_back();
+        }
+        while self.position() < position {
+            self.step_forward()
+                .map_err(|error| (self.position(), error))?;
+        }
+        Ok(())
+    }
+
+    /// Steps forward until `predicate` holds for the ship, and returns the position at which it
+    /// does. Returns `None` when it doesn't hold after the last instruction either.
+    pub fn find(
+        &mut self,
+        predicate: impl Fn(&Ship) -> bool,
+    ) -> Result<Option<usize>, (usize, MoveError)> {
+        loop {
+            if predicate(&self.ship) {
+                return Ok(Some(self.position()));
+            }
+            if !self
+                .step_forward()
+                .map_err(|error| (self.position(), error))?
+            {
+                return Ok(None);
+            }
+        }
+    }
+}
+
+pub fn challange1(
+    (ship, instructions): (Ship, Vec<Instruction>),
+) -> Result<String, (usize, MoveError)> {
     let mut ship = ship;
-    for instruction in instructions {
-        ship.execute_challange2(instruction);
+    run(&mut ship, &CrateMover9000, &instructions)?;
+    Ok(ship.top_crate_labels())
+}
+
+pub fn challange2(
+    (ship, instructions): (Ship, Vec<Instruction>),
+) -> Result<String, (usize, MoveError)> {
+    let mut ship = ship;
+    run(&mut ship, &CrateMover9001, &instructions)?;
+    Ok(ship.top_crate_labels())
+}
+
+fn solve_error((index, error): (usize, MoveError)) -> SolveError {
+    SolveError::new(format!("instruction {}: {error}", index + 1))
+}
+
+pub struct Day05;
+
+impl Solution for Day05 {
+    const DAY: u8 = 5;
+
+    type Input = (Ship, Vec<Instruction>);
+
+    fn parse(text: &str) -> Result<Self::Input, ParseError> {
+        parse(text)
+    }
+
+    fn challange1(input: &Self::Input) -> Result<Answer, SolveError> {
+        challange1(input.clone())
+            .map(Answer::from)
+            .map_err(solve_error)
+    }
+
+    fn challange2(input: &Self::Input) -> Result<Answer, SolveError> {
+        challange2(input.clone())
+            .map(Answer::from)
+            .map_err(solve_error)
     }
-    ship.layout
-        .iter()
-        .map(|vec| vec.front().unwrap_or(&' '))
-        .collect()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::input::InputDir;
 
     const EXAMPLE_TEXT: &str = "\
 .   [D]    
@@ -125,22 +580,10 @@ move 1 from 1 to 2
 
     #[test]
     fn parse_example() {
-        let (ship, instructions) = parse(EXAMPLE_TEXT);
+        let (ship, instructions) = parse(EXAMPLE_TEXT).unwrap();
         assert_eq!(
             ship,
-            Ship {
-                layout: [
-                    ['N', 'Z',].into(),
-                    ['D', 'C', 'M',].into(),
-                    ['P',].into(),
-                    [].into(),
-                    [].into(),
-                    [].into(),
-                    [].into(),
-                    [].into(),
-                    [].into()
-                ]
-            }
+            Ship::new([vec!["N", "Z"], vec!["D", "C", "M"], vec!["P"]])
         );
         assert_eq!(
             instructions,
@@ -169,15 +612,312 @@ move 1 from 1 to 2
         );
     }
 
+    #[test]
+    fn parse_invalid() {
+        let error = parse("[A]\n 1 \n\nmove 1 form 1 to 1").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 4, column 8: expected `from`, found `form`"
+        );
+        let error = parse("[A]\n 1 \n\nmove one from 1 to 1").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 4, column 6: expected an amount of crates, found `one`"
+        );
+        let error = parse("[A]\n 1 \nmove 1 from 1 to 1").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 4, column 1: expected an empty line after the drawing"
+        );
+        let error = parse("    [A]\n 1   2 \n\nmove 1 from 3 to 1\n").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 4, column 13: expected a stack number from 1 to 2, found `3`"
+        );
+        let error = parse("    [A]\n 1   2 \n\nmove 1 from 2 to 0\n").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 4, column 18: expected a stack number from 1 to 2, found `0`"
+        );
+        let error = parse("[A]\n 1 3\n\n").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 2, column 4: expected stack number 2, found `3`"
+        );
+        let error = parse("    [A]\n 1 \n\n").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 1, column 5: expected a crate above a stack number, found `[A]`"
+        );
+        let error = parse("[A\n 1 \n\n").unwrap_err();
+        assert_eq!(error.to_string(), "line 1, column 3: expected `]`");
+        let error = parse("\n").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 1, column 1: expected a drawing of the stacks"
+        );
+    }
+
+    #[test]
+    fn parse_large_ship() {
+        let text = "\
+.                                   [D]
+[A] [B]                             [C] [E]
+ 1   2   3   4   5   6   7   8   9   10  11 
+
+move 1 from 10 to 3
+";
+        let (ship, _) = parse(text).unwrap();
+        assert_eq!(ship.stack_count(), 11);
+        assert_eq!(
+            ship.top_crates(),
+            vec![
+                Some("A"),
+                Some("B"),
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                Some("D"),
+                Some("E")
+            ]
+        );
+        assert_eq!(challange1(parse(text).unwrap()).unwrap(), "ABDCE");
+    }
+
+    #[test]
+    fn parse_labels() {
+        let text = "\
+.      [ΔΣ]
+[Ab]   [🦀]
+ 1      2   
+
+move 2 from 2 to 1
+";
+        let (ship, _) = parse(text).unwrap();
+        assert_eq!(ship, Ship::new([vec!["Ab"], vec!["ΔΣ", "🦀"]]));
+        assert_eq!(challange1(parse(text).unwrap()).unwrap(), "🦀");
+        assert_eq!(challange2(parse(text).unwrap()).unwrap(), "ΔΣ");
+    }
+
     #[test]
     fn challange1_example() {
-        let data = parse(EXAMPLE_TEXT);
-        assert_eq!(challange1(data), "CMZ      ");
+        let data = parse(EXAMPLE_TEXT).unwrap();
+        assert_eq!(challange1(data).unwrap(), "CMZ");
     }
 
     #[test]
     fn challange2_example() {
-        let data = parse(EXAMPLE_TEXT);
-        assert_eq!(challange2(data), "MCD      ");
+        let data = parse(EXAMPLE_TEXT).unwrap();
+        assert_eq!(challange2(data).unwrap(), "MCD");
+    }
+
+    #[test]
+    fn challange_not_enough_crates() {
+        let data = parse("    [A]\n 1   2 \n\nmove 1 from 2 to 1\nmove 2 from 1 to 2\n").unwrap();
+        assert_eq!(
+            challange1(data.clone()),
+            Err((
+                1,
+                MoveError::NotEnoughCrates {
+                    stack: 1,
+                    requested: 2,
+                    available: 1
+                }
+            ))
+        );
+        assert_eq!(
+            Day05::challange2(&data).unwrap_err().to_string(),
+            "instruction 2: cannot move 2 crates from stack 1, which has 1"
+        );
+    }
+
+    #[test]
+    fn cranes_example() {
+        let (ship, instructions) = parse(EXAMPLE_TEXT).unwrap();
+        let cranes: [(&dyn Crane, &str); 5] = [
+            (&CrateMover9000, "CMZ"),
+            (&CrateMover9001, "MCD"),
+            (&CapacityLimited::new(1), "CMZ"),
+            (&CapacityLimited::new(2), "MCZ"),
+            (&CapacityLimited::new(3), "MCD"),
+        ];
+        for (crane, expected) in cranes {
+            let mut ship = ship.clone();
+            run(&mut ship, crane, &instructions).unwrap();
+            assert_eq!(ship.top_crate_labels(), expected);
+        }
+    }
+
+    #[test]
+    fn execute_invalid() {
+        let (mut ship, _) = parse(EXAMPLE_TEXT).unwrap();
+        let original = ship.clone();
+        assert_eq!(
+            ship.execute(&CrateMover9000, &Instruction::new(1, 4, 1)),
+            Err(MoveError::MissingStack { stack: 4 })
+        );
+        assert_eq!(
+            ship.execute(&CrateMover9000, &Instruction::new(1, 1, 0)),
+            Err(MoveError::MissingStack { stack: 0 })
+        );
+        let error = ship
+            .execute(&CrateMover9001, &Instruction::new(3, 1, 2))
+            .unwrap_err();
+        assert_eq!(
+            error,
+            MoveError::NotEnoughCrates {
+                stack: 1,
+                requested: 3,
+                available: 2
+            }
+        );
+        assert_eq!(
+            error.to_string(),
+            "cannot move 3 crates from stack 1, which has 2"
+        );
+        assert_eq!(ship, original);
+
+        let instructions = [Instruction::new(2, 1, 3), Instruction::new(1, 1, 2)];
+        assert_eq!(
+            run(&mut ship, &CrateMover9000, &instructions),
+            Err((
+                1,
+                MoveError::NotEnoughCrates {
+                    stack: 1,
+                    requested: 1,
+                    available: 0
+                }
+            ))
+        );
+    }
+
+    #[test]
+    fn render_example() {
+        let (mut ship, instructions) = parse(EXAMPLE_TEXT).unwrap();
+        assert_eq!(
+            render(&ship, &instructions),
+            EXAMPLE_TEXT.replacen('.', " ", 1)
+        );
+
+        ship.execute(&CrateMover9001, &instructions[0]).unwrap();
+        assert_eq!(
+            ship.to_string(),
+            "\
+[D]        
+[N] [C]    
+[Z] [M] [P]
+ 1   2   3 "
+        );
+    }
+
+    #[test]
+    fn render_round_trip() {
+        let texts = [
+            EXAMPLE_TEXT.to_string(),
+            InputDir::default().load(5).unwrap(),
+            "\
+.   [D]                                    
+[A] [B]                             [C] [E]
+ 1   2   3   4   5   6   7   8   9   10  11 
+
+move 1 from 10 to 3
+"
+            .to_string(),
+            "\
+.      [ΔΣ]
+[Ab]   [🦀]
+ 1      2   
+
+move 2 from 2 to 1
+"
+            .to_string(),
+        ];
+        for text in texts {
+            let (ship, instructions) = parse(&text).unwrap();
+            let rendered = render(&ship, &instructions);
+            assert_eq!(parse(&rendered).unwrap(), (ship, instructions));
+        }
+
+        let text = InputDir::default().load(5).unwrap();
+        let (ship, instructions) = parse(&text).unwrap();
+        assert_eq!(render(&ship, &instructions), text);
+    }
+
+    #[test]
+    fn replay_example() {
+        let (ship, instructions) = parse(EXAMPLE_TEXT).unwrap();
+        let mut replay = Replay::new(ship.clone(), &CrateMover9000, &instructions);
+        assert!(!replay.step_back());
+        assert!(replay.step_forward().unwrap());
+        assert!(replay.step_forward().unwrap());
+        assert_eq!(replay.position(), 2);
+        assert_eq!(
+            replay.steps()[1],
+            Step {
+                instruction: 1,
+                crates: vec!["D".to_string(), "N".to_string(), "Z".to_string()],
+                top_crates: vec![None, Some("C".to_string()), Some("Z".to_string())],
+            }
+        );
+
+        replay.jump_to(4).unwrap();
+        assert!(replay.is_finished());
+        assert!(!replay.step_forward().unwrap());
+        assert_eq!(replay.ship().top_crate_labels(), "CMZ");
+        let history: Vec<String> = replay
+            .top_crates_history()
+            .iter()
+            .map(|top| top.iter().flatten().map(String::as_str).collect())
+            .collect();
+        assert_eq!(history, vec!["NDP", "DCP", "CZ", "MZ", "CMZ"]);
+
+        replay.jump_to(1).unwrap();
+        assert_eq!(replay.position(), 1);
+        assert_eq!(replay.top_crates_history().len(), 2);
+        replay.jump_to(0).unwrap();
+        assert_eq!(replay.ship(), &ship);
+    }
+
+    #[test]
+    fn replay_find() {
+        let (ship, instructions) = parse(EXAMPLE_TEXT).unwrap();
+        let empty_stack = |ship: &Ship| ship.top_crates().contains(&None);
+        let mut replay = Replay::new(ship.clone(), &CrateMover9001, &instructions);
+        assert_eq!(replay.find(empty_stack), Ok(Some(2)));
+        assert_eq!(
+            replay.find(|ship| ship.stack(1).unwrap().front().is_some_and(|top| top == "M")),
+            Ok(Some(4))
+        );
+        assert_eq!(replay.find(|ship| ship.stack_count() > 3), Ok(None));
+
+        let mut replay = Replay::new(ship.clone(), &CrateMover9001, &instructions);
+        let on_top = |ship: &Ship| ship.stack(3).unwrap().front().is_some_and(|top| top == "D");
+        assert_eq!(replay.find(on_top), Ok(Some(2)));
+        let mut replay = Replay::new(ship, &CrateMover9000, &instructions);
+        assert_eq!(replay.find(on_top), Ok(None));
+    }
+
+    #[test]
+    fn replay_invalid() {
+        let (ship, _) = parse(EXAMPLE_TEXT).unwrap();
+        let instructions = [Instruction::new(1, 1, 2), Instruction::new(2, 1, 2)];
+        let mut replay = Replay::new(ship, &CrateMover9000, &instructions);
+        assert_eq!(
+            replay.jump_to(2),
+            Err((
+                1,
+                MoveError::NotEnoughCrates {
+                    stack: 1,
+                    requested: 2,
+                    available: 1
+                }
+            ))
+        );
+        assert_eq!(replay.position(), 1);
+        assert_eq!(replay.ship().top_crate_labels(), "ZNP");
     }
 }
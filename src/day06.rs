@@ -15,7 +15,14 @@
  * along with this program.  If not, see <http://www.gnu.org/licenses/>.
  */
 
-use std::collections::HashSet;
+use crate::{
+    parse::ParseError,
+    solution::{Answer, Solution, SolveError},
+};
+use std::{
+    collections::{HashSet, VecDeque},
+    io::{self, Read},
+};
 
 #[derive(Clone, Debug, PartialEq, Eq, Default)]
 pub struct Datasteam {
@@ -36,25 +43,251 @@ pub fn is_unique(data: &[u8]) -> bool {
     set.len() == data.len()
 }
 
-pub fn find_start_of_stream(datastream: &Datasteam, marker_len: usize) -> usize {
-    datastream
-        .data
-        .as_bytes()
-        .windows(marker_len)
-        .enumerate()
-        .find(|(_, window)| is_unique(window))
-        .unwrap()
-        .0
-        + marker_len
+/// Detects windows of distinct bytes, one byte at a time.
+///
+/// Only the last position of each byte value is kept, so each byte takes constant time and the
+/// memory doesn't depend on the marker length or the length of the stream.
+#[derive(Clone, Debug)]
+pub struct MarkerDetector {
+    marker_len: usize,
+    /// Number of bytes pushed up to and including the last occurrence of each byte value.
+    last_seen: [usize; 256],
+    /// Position of the first byte of the longest run of distinct bytes that ends at the last byte.
+    run_start: usize,
+    position: usize,
 }
 
-pub fn challange1(datastream: &Datasteam) -> usize {
+impl MarkerDetector {
+    /// A `marker_len` above 256 never matches, as there are only 256 different bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `marker_len` is zero.
+    pub fn new(marker_len: usize) -> Self {
+        assert!(marker_len > 0, "a marker must be at least one byte");
+        Self {
+            marker_len,
+            last_seen: [0; 256],
+            run_start: 0,
+            position: 0,
+        }
+    }
+
+    pub fn marker_len(&self) -> usize {
+        self.marker_len
+    }
+
+    /// Number of bytes that are pushed.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Adds the next byte and returns whether the last `marker_len` bytes are all different.
+    pub fn push(&mut self, byte: u8) -> bool {
+        let last_seen = &mut self.last_seen[usize::from(byte)];
+        self.run_start = self.run_start.max(*last_seen);
+        self.position += 1;
+        *last_seen = self.position;
+        self.position - self.run_start >= self.marker_len
+    }
+
+    /// Forgets the bytes pushed so far, so the next marker only uses bytes pushed after this.
+    pub fn reset(&mut self) {
+        self.run_start = self.position;
+    }
+}
+
+/// Reads `reader` until the first marker, and returns the number of bytes up to and including it.
+pub fn find_marker(mut reader: impl Read, marker_len: usize) -> io::Result<Option<usize>> {
+    let mut detector = MarkerDetector::new(marker_len);
+    let mut buffer = [0; 8192];
+    loop {
+        let len = match reader.read(&mut buffer) {
+            Ok(0) => return Ok(None),
+            Ok(len) => len,
+            Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+            Err(error) => return Err(error),
+        };
+        for &byte in &buffer[..len] {
+            if detector.push(byte) {
+                return Ok(Some(detector.position()));
+            }
+        }
+    }
+}
+
+/// A window of distinct bytes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Marker {
+    pub marker_len: usize,
+    /// Number of bytes up to and including the marker.
+    pub position: usize,
+}
+
+/// Iterator over the markers of one or more lengths, in a single pass over the data.
+///
+/// Markers are ordered by position, and markers at the same position by the order of their
+/// lengths. Every window of distinct bytes is reported, so markers may overlap.
+#[derive(Clone, Debug)]
+pub struct Markers<'a> {
+    data: std::slice::Iter<'a, u8>,
+    detectors: Vec<MarkerDetector>,
+    found: VecDeque<Marker>,
+}
+
+impl<'a> Markers<'a> {
+    /// # Panics
+    ///
+    /// Panics when one of the lengths is zero.
+    pub fn new(data: &'a [u8], marker_lens: &[usize]) -> Self {
+        Self {
+            data: data.iter(),
+            detectors: marker_lens
+                .iter()
+                .copied()
+                .map(MarkerDetector::new)
+                .collect(),
+            found: VecDeque::new(),
+        }
+    }
+}
+
+impl Iterator for Markers<'_> {
+    type Item = Marker;
+
+    fn next(&mut self) -> Option<Marker> {
+        while self.found.is_empty() {
+            let &byte = self.data.next()?;
+            for detector in &mut self.detectors {
+                if detector.push(byte) {
+                    self.found.push_back(Marker {
+                        marker_len: detector.marker_len(),
+                        position: detector.position(),
+                    });
+                }
+            }
+        }
+        self.found.pop_front()
+    }
+}
+
+impl Datasteam {
+    /// Returns the positions after every window of `marker_len` distinct bytes.
+    pub fn markers(&self, marker_len: usize) -> impl Iterator<Item = usize> + '_ {
+        Markers::new(self.data.as_bytes(), &[marker_len]).map(|marker| marker.position)
+    }
+
+    /// Returns the markers of all lengths in `marker_lens`.
+    pub fn markers_of(&self, marker_lens: &[usize]) -> Markers<'_> {
+        Markers::new(self.data.as_bytes(), marker_lens)
+    }
+
+    /// Splits the stream into packets that each start with a marker.
+    pub fn packets(&self, marker_len: usize) -> Packets<'_> {
+        Packets::new(self.data.as_bytes(), marker_len)
+    }
+}
+
+/// A marker and the data that follows it, up to the next marker.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Packet<'a> {
+    /// Offset of the marker in the stream.
+    pub offset: usize,
+    pub marker: &'a [u8],
+    pub payload: &'a [u8],
+}
+
+/// Iterator that splits a stream at its markers.
+///
+/// Markers don't overlap: after a marker, the next one only uses bytes after it. Data before the
+/// first marker is skipped.
+#[derive(Clone, Debug)]
+pub struct Packets<'a> {
+    data: &'a [u8],
+    detector: MarkerDetector,
+    next_marker: Option<usize>,
+}
+
+impl<'a> Packets<'a> {
+    /// # Panics
+    ///
+    /// Panics when `marker_len` is zero.
+    pub fn new(data: &'a [u8], marker_len: usize) -> Self {
+        let mut packets = Self {
+            data,
+            detector: MarkerDetector::new(marker_len),
+            next_marker: None,
+        };
+        packets.next_marker = packets.find_next_marker();
+        packets
+    }
+
+    /// Returns the position after the next marker.
+    fn find_next_marker(&mut self) -> Option<usize> {
+        self.detector.reset();
+        while let Some(&byte) = self.data.get(self.detector.position()) {
+            if self.detector.push(byte) {
+                return Some(self.detector.position());
+            }
+        }
+        None
+    }
+}
+
+impl<'a> Iterator for Packets<'a> {
+    type Item = Packet<'a>;
+
+    fn next(&mut self) -> Option<Packet<'a>> {
+        let start = self.next_marker?;
+        self.next_marker = self.find_next_marker();
+        let marker_len = self.detector.marker_len();
+        let end = self
+            .next_marker
+            .map_or(self.data.len(), |next| next - marker_len);
+        Some(Packet {
+            offset: start - marker_len,
+            marker: &self.data[start - marker_len..start],
+            payload: &self.data[start..end],
+        })
+    }
+}
+
+/// Returns the number of bytes up to and including the first marker, if there is one.
+pub fn find_start_of_stream(datastream: &Datasteam, marker_len: usize) -> Option<usize> {
+    datastream.markers(marker_len).next()
+}
+
+pub fn challange1(datastream: &Datasteam) -> Option<usize> {
     find_start_of_stream(datastream, 4)
 }
-pub fn challange2(datastream: &Datasteam) -> usize {
+pub fn challange2(datastream: &Datasteam) -> Option<usize> {
     find_start_of_stream(datastream, 14)
 }
 
+pub struct Day06;
+
+impl Solution for Day06 {
+    const DAY: u8 = 6;
+
+    type Input = Datasteam;
+
+    fn parse(text: &str) -> Result<Self::Input, ParseError> {
+        Ok(parse(text))
+    }
+
+    fn challange1(input: &Self::Input) -> Result<Answer, SolveError> {
+        challange1(input)
+            .map(Answer::from)
+            .ok_or_else(|| SolveError::new("no start-of-packet marker of 4 different characters"))
+    }
+
+    fn challange2(input: &Self::Input) -> Result<Answer, SolveError> {
+        challange2(input)
+            .map(Answer::from)
+            .ok_or_else(|| SolveError::new("no start-of-message marker of 14 different characters"))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -68,29 +301,166 @@ mod tests {
     #[test]
     fn challange1_example() {
         let data = parse(EXAMPLE_TEXT1);
-        assert_eq!(challange1(&data), 7);
+        assert_eq!(challange1(&data), Some(7));
         let data = parse(EXAMPLE_TEXT2);
-        assert_eq!(challange1(&data), 5);
+        assert_eq!(challange1(&data), Some(5));
         let data = parse(EXAMPLE_TEXT3);
-        assert_eq!(challange1(&data), 6);
+        assert_eq!(challange1(&data), Some(6));
         let data = parse(EXAMPLE_TEXT4);
-        assert_eq!(challange1(&data), 10);
+        assert_eq!(challange1(&data), Some(10));
         let data = parse(EXAMPLE_TEXT5);
-        assert_eq!(challange1(&data), 11);
+        assert_eq!(challange1(&data), Some(11));
     }
 
     #[test]
     fn challange2_example() {
         let data = parse(EXAMPLE_TEXT1);
-        assert_eq!(challange2(&data), 19);
+        assert_eq!(challange2(&data), Some(19));
         let data = parse(EXAMPLE_TEXT2);
-        assert_eq!(challange2(&data), 23);
+        assert_eq!(challange2(&data), Some(23));
         let data = parse(EXAMPLE_TEXT3);
-        assert_eq!(challange2(&data), 23);
+        assert_eq!(challange2(&data), Some(23));
         let data = parse(EXAMPLE_TEXT4);
-        assert_eq!(challange2(&data), 29);
+        assert_eq!(challange2(&data), Some(29));
         let data = parse(EXAMPLE_TEXT5);
-        assert_eq!(challange2(&data), 26);
+        assert_eq!(challange2(&data), Some(26));
+    }
+
+    /// Reader that returns a single byte per call, and fails at the end when asked to.
+    struct Trickle<'a> {
+        data: &'a [u8],
+        fail: bool,
+    }
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            match self.data.split_first() {
+                Some((&byte, rest)) => {
+                    buf[0] = byte;
+                    self.data = rest;
+                    Ok(1)
+                }
+                None if self.fail => Err(io::Error::other("connection lost")),
+                None => Ok(0),
+            }
+        }
+    }
+
+    #[test]
+    fn find_marker_example() {
+        let examples = [
+            (EXAMPLE_TEXT1, 7, 19),
+            (EXAMPLE_TEXT2, 5, 23),
+            (EXAMPLE_TEXT3, 6, 23),
+            (EXAMPLE_TEXT4, 10, 29),
+            (EXAMPLE_TEXT5, 11, 26),
+        ];
+        for (text, packet, message) in examples {
+            assert_eq!(find_marker(text.as_bytes(), 4).unwrap(), Some(packet));
+            let reader = Trickle {
+                data: text.as_bytes(),
+                fail: false,
+            };
+            assert_eq!(find_marker(reader, 14).unwrap(), Some(message));
+        }
+    }
+
+    #[test]
+    fn find_marker_missing() {
+        assert_eq!(find_marker("abcabcabc".as_bytes(), 4).unwrap(), None);
+        assert_eq!(find_marker(io::empty(), 4).unwrap(), None);
+        let reader = Trickle {
+            data: b"aaaa",
+            fail: true,
+        };
+        assert_eq!(
+            find_marker(reader, 2).unwrap_err().to_string(),
+            "connection lost"
+        );
+    }
+
+    #[test]
+    fn marker_detector() {
+        let mut detector = MarkerDetector::new(3);
+        let found: Vec<bool> = b"abacabbcd"
+            .iter()
+            .map(|&byte| detector.push(byte))
+            .collect();
+        assert_eq!(
+            found,
+            vec![false, false, false, true, false, true, false, false, true]
+        );
+        assert_eq!(detector.position(), 9);
+        let mut detector = MarkerDetector::new(1);
+        assert!(detector.push(b'a'));
+        assert!(detector.push(b'a'));
+        let mut detector = MarkerDetector::new(257);
+        assert!(!(0..=u8::MAX)
+            .chain(0..=u8::MAX)
+            .any(|byte| detector.push(byte)));
+        let mut detector = MarkerDetector::new(256);
+        assert!((0..=u8::MAX)
+            .map(|byte| detector.push(byte))
+            .last()
+            .unwrap());
+    }
+
+    #[test]
+    fn missing_marker() {
+        let data = parse("abcabcabcd");
+        assert_eq!(challange1(&data), Some(10));
+        assert_eq!(challange2(&data), None);
+        assert_eq!(challange1(&parse("")), None);
+        let data = Day06::parse("abcabcabcd").unwrap();
+        assert_eq!(Day06::challange1(&data).unwrap(), Answer::Integer(10));
+        assert_eq!(
+            Day06::challange2(&data).unwrap_err().to_string(),
+            "no start-of-message marker of 14 different characters"
+        );
+        let data = Day06::parse("").unwrap();
+        assert_eq!(
+            Day06::challange1(&data).unwrap_err().to_string(),
+            "no start-of-packet marker of 4 different characters"
+        );
+    }
+
+    #[test]
+    fn markers_example() {
+        let data = parse(EXAMPLE_TEXT2);
+        let markers: Vec<usize> = data.markers(4).take(5).collect();
+        assert_eq!(markers, vec![5, 6, 7, 8, 9]);
+        assert_eq!(data.markers(14).next(), Some(23));
+        assert_eq!(data.markers(27).next(), None);
+
+        let markers: Vec<(usize, usize)> = data
+            .markers_of(&[4, 14])
+            .map(|marker| (marker.marker_len, marker.position))
+            .filter(|&(_, position)| (21..=24).contains(&position))
+            .collect();
+        assert_eq!(
+            markers,
+            vec![(4, 21), (4, 22), (4, 23), (14, 23), (4, 24), (14, 24)]
+        );
+        assert_eq!(parse("").markers_of(&[4, 14]).count(), 0);
+    }
+
+    #[test]
+    fn packets_example() {
+        let data = parse("aabcdxxefghijjjjklmnopqr");
+        let packets: Vec<(usize, &[u8], &[u8])> = data
+            .packets(4)
+            .map(|packet| (packet.offset, packet.marker, packet.payload))
+            .collect();
+        assert_eq!(
+            packets,
+            vec![
+                (1, &b"abcd"[..], &b"x"[..]),
+                (6, b"xefg", b"hijjj"),
+                (15, b"jklm", b""),
+                (19, b"nopq", b"r"),
+            ]
+        );
+        assert_eq!(parse("aaaa").packets(2).count(), 0);
     }
 
     // #[test]
@@ -15,7 +15,14 @@
  * along with this program.  If not, see <http://www.gnu.org/licenses/>.
  */
 
-use std::{collections::VecDeque, path::PathBuf};
+use crate::{
+    parse::{lines, ParseError},
+    solution::{Answer, Solution, SolveError},
+};
+use std::{
+    collections::VecDeque,
+    path::{Path, PathBuf},
+};
 
 #[derive(Clone, Debug, PartialEq, Eq, Default)]
 pub struct Directory {
@@ -46,14 +53,18 @@ impl Directory {
     }
 }
 
-pub fn parse(text: &str) -> Vec<Directory> {
+pub fn parse(text: &str) -> Result<Vec<Directory>, ParseError> {
     let mut directories = Vec::new();
-    let mut lines = text.lines().collect::<VecDeque<_>>();
+    let mut lines = lines(text).collect::<VecDeque<_>>();
     let mut current_path = PathBuf::new();
-    while !lines.is_empty() {
-        let command = lines.pop_front().unwrap();
-        if command.starts_with("$ cd") {
-            let directory = command.strip_prefix("$ cd ").unwrap();
+    match lines.front() {
+        Some(first) if first.text() == "$ cd /" => {}
+        Some(first) => return Err(first.error(first.text(), "`$ cd /`")),
+        None => return Err(ParseError::end_of_input(text, "`$ cd /`")),
+    }
+    while let Some(command) = lines.pop_front() {
+        if command.text().starts_with("$ cd") {
+            let directory = command.strip_prefix("$ cd ")?;
             if directory == "/" {
                 current_path.clear();
                 current_path.push("/");
@@ -62,24 +73,29 @@ pub fn parse(text: &str) -> Vec<Directory> {
             } else {
                 current_path.push(directory);
             }
-        } else if command.starts_with("$ ls") {
+        } else if command.text() == "$ ls" {
             let mut new_directory = Directory {
                 path: current_path.clone(),
                 ..Default::default()
             };
-            while !lines.front().unwrap_or(&"$").starts_with('$') {
+            while lines
+                .front()
+                .is_some_and(|entry| !entry.text().starts_with('$'))
+            {
                 let entry = lines.pop_front().unwrap();
-                if !entry.starts_with("dir") {
-                    let (size, name) = entry.split_once(' ').unwrap();
+                if !entry.text().starts_with("dir ") {
+                    let (size, name) = entry.split_once(entry.text(), ' ')?;
                     new_directory
                         .files
-                        .push((name.to_string(), size.parse().unwrap()))
+                        .push((name.to_string(), entry.parse(size, "a file size")?))
                 }
             }
             directories.push(new_directory);
+        } else {
+            return Err(command.error(command.text(), "a command (`$ cd` or `$ ls`)"));
         }
     }
-    directories
+    Ok(directories)
 }
 
 pub fn challange1(directories: &Vec<Directory>) -> usize {
@@ -90,23 +106,45 @@ pub fn challange1(directories: &Vec<Directory>) -> usize {
         .sum()
 }
 
-pub fn challange2(directories: &Vec<Directory>) -> usize {
-    let total_disk = 70000000;
-    let update_size = 30000000;
+/// Returns `None` when the root directory isn't listed. Otherwise there is always an answer, as
+/// deleting the root directory frees enough space.
+pub fn challange2(directories: &Vec<Directory>) -> Option<usize> {
+    let total_disk: usize = 70000000;
+    let update_size: usize = 30000000;
     let used_disk = directories
         .iter()
-        .find(|dir| dir.path == PathBuf::from("/"))
-        .unwrap()
+        .find(|dir| dir.path == Path::new("/"))?
         .total_size(directories);
-    let free_disk = total_disk - used_disk;
-    let to_be_freeed = update_size - free_disk;
+    let free_disk = total_disk.saturating_sub(used_disk);
+    let to_be_freeed = update_size.saturating_sub(free_disk);
 
     directories
         .iter()
         .map(|directory| directory.total_size(directories))
         .filter(|&size| size >= to_be_freeed)
         .min()
-        .unwrap()
+}
+
+pub struct Day07;
+
+impl Solution for Day07 {
+    const DAY: u8 = 7;
+
+    type Input = Vec<Directory>;
+
+    fn parse(text: &str) -> Result<Self::Input, ParseError> {
+        parse(text)
+    }
+
+    fn challange1(input: &Self::Input) -> Result<Answer, SolveError> {
+        Ok(challange1(input).into())
+    }
+
+    fn challange2(input: &Self::Input) -> Result<Answer, SolveError> {
+        challange2(input)
+            .map(Answer::from)
+            .ok_or_else(|| SolveError::new("no listing of the root directory `/`"))
+    }
 }
 
 #[cfg(test)]
@@ -140,7 +178,7 @@ $ ls
 
     #[test]
     fn parse_example() {
-        let directories = parse(EXAMPLE_TEXT);
+        let directories = parse(EXAMPLE_TEXT).unwrap();
         assert_eq!(
             directories,
             vec![
@@ -178,7 +216,7 @@ $ ls
 
     #[test]
     fn total_size_example() {
-        let directories = parse(EXAMPLE_TEXT);
+        let directories = parse(EXAMPLE_TEXT).unwrap();
         let mut total_sizes = directories
             .iter()
             .map(|directory| directory.total_size(&directories));
@@ -188,15 +226,54 @@ $ ls
         assert_eq!(total_sizes.next().unwrap(), 24933642);
     }
 
+    #[test]
+    fn parse_invalid() {
+        let error = parse("$ cd /\n$ ls\n12a b.txt").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 3, column 1: expected a file size, found `12a`"
+        );
+        let error = parse("$ cd /\n$ rm -rf").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 2, column 1: expected a command (`$ cd` or `$ ls`), found `$ rm -rf`"
+        );
+        let error = parse("$ ls\n").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 1, column 1: expected `$ cd /`, found `$ ls`"
+        );
+        let error = parse("").unwrap_err();
+        assert_eq!(error.to_string(), "line 1, column 1: expected `$ cd /`");
+    }
+
+    #[test]
+    fn challange2_small_disk() {
+        let data = parse("$ cd /\n$ ls\n1 a.txt").unwrap();
+        assert_eq!(challange2(&data), Some(1));
+    }
+
+    #[test]
+    fn challange2_missing_root() {
+        let data = parse("$ cd /").unwrap();
+        assert_eq!(challange2(&data), None);
+        let data = parse("$ cd /\n$ cd a\n$ ls\n5 x").unwrap();
+        assert_eq!(challange2(&data), None);
+        assert_eq!(
+            Day07::challange2(&data).unwrap_err().to_string(),
+            "no listing of the root directory `/`"
+        );
+    }
+
     #[test]
     fn challange1_example() {
-        let data = parse(EXAMPLE_TEXT);
+        let data = parse(EXAMPLE_TEXT).unwrap();
         assert_eq!(challange1(&data), 95437);
     }
 
     #[test]
     fn challange2_example() {
-        let data = parse(EXAMPLE_TEXT);
-        assert_eq!(challange2(&data), 24933642);
+        let data = parse(EXAMPLE_TEXT).unwrap();
+        assert_eq!(challange2(&data), Some(24933642));
     }
 }
@@ -15,22 +15,46 @@
  * along with this program.  If not, see <http://www.gnu.org/licenses/>.
  */
 
+use crate::{
+    parse::{lines, Line, ParseError},
+    solution::{Answer, Solution, SolveError},
+};
 use array2d::Array2D;
-use std::usize;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Map {
     heights: Array2D<u8>,
 }
 
-pub fn parse(text: &str) -> Map {
-    let heights: Vec<Vec<u8>> = text
-        .lines()
-        .map(|line| line.chars().map(|char| char as u8 - b'0').collect())
-        .collect();
-    Map {
-        heights: Array2D::from_rows(&heights).unwrap(),
+pub fn parse(text: &str) -> Result<Map, ParseError> {
+    let lines = lines(text).collect::<Vec<_>>();
+    let heights = lines.iter().map(parse_row).collect::<Result<Vec<_>, _>>()?;
+    match heights.first() {
+        None => return Err(ParseError::end_of_input(text, "a row of trees")),
+        Some(row) if row.is_empty() => return Err(lines[0].error_at_end("a tree height")),
+        Some(_) => {}
+    }
+    if let Some((line, _)) = lines
+        .iter()
+        .zip(&heights)
+        .find(|(_, row)| row.len() != heights[0].len())
+    {
+        let expected = format!("a row of {} trees", heights[0].len());
+        return Err(line.error(line.text(), expected));
     }
+    Ok(Map {
+        heights: Array2D::from_rows(&heights).unwrap(),
+    })
+}
+
+fn parse_row(line: &Line) -> Result<Vec<u8>, ParseError> {
+    line.text()
+        .char_indices()
+        .map(|(i, char)| match char {
+            '0'..='9' => Ok(char as u8 - b'0'),
+            _ => Err(line.error(&line.text()[i..i + char.len_utf8()], "a tree height")),
+        })
+        .collect()
 }
 
 fn scenic_score_iter(tree_height: u8, iter: impl Iterator<Item = u8>) -> usize {
@@ -127,6 +151,26 @@ pub fn challange2(map: &Map) -> usize {
         .unwrap()
 }
 
+pub struct Day08;
+
+impl Solution for Day08 {
+    const DAY: u8 = 8;
+
+    type Input = Map;
+
+    fn parse(text: &str) -> Result<Self::Input, ParseError> {
+        parse(text)
+    }
+
+    fn challange1(input: &Self::Input) -> Result<Answer, SolveError> {
+        Ok(challange1(input).into())
+    }
+
+    fn challange2(input: &Self::Input) -> Result<Answer, SolveError> {
+        Ok(challange2(input).into())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -140,7 +184,7 @@ mod tests {
 
     #[test]
     fn parse_example() {
-        let data = parse(EXAMPLE_TEXT);
+        let data = parse(EXAMPLE_TEXT).unwrap();
         assert_eq!(
             data.heights.as_rows(),
             vec![
@@ -153,9 +197,33 @@ mod tests {
         );
     }
 
+    #[test]
+    fn parse_invalid() {
+        let error = parse("").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 1, column 1: expected a row of trees"
+        );
+        let error = parse("\n").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 1, column 1: expected a tree height"
+        );
+        let error = parse("303\n2a5").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 2, column 2: expected a tree height, found `a`"
+        );
+        let error = parse("303\n25").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 2, column 1: expected a row of 3 trees, found `25`"
+        );
+    }
+
     #[test]
     fn iters_example() {
-        let data = parse(EXAMPLE_TEXT);
+        let data = parse(EXAMPLE_TEXT).unwrap();
         assert_eq!(data.top_iter(1, 2).copied().collect::<Vec<_>>(), vec![3]);
 
         assert_eq!(
@@ -188,7 +256,7 @@ mod tests {
 
     #[test]
     fn visibility_example() {
-        let data = parse(EXAMPLE_TEXT);
+        let data = parse(EXAMPLE_TEXT).unwrap();
         assert_eq!(
             data.visibility().as_rows(),
             vec![
@@ -203,7 +271,7 @@ mod tests {
 
     #[test]
     fn scenic_score_example() {
-        let data = parse(EXAMPLE_TEXT);
+        let data = parse(EXAMPLE_TEXT).unwrap();
         let scenic_score = data.scenic_score();
         assert_eq!(scenic_score.get(1, 2).unwrap(), &4);
         assert_eq!(scenic_score.get(3, 2).unwrap(), &8);
@@ -211,13 +279,13 @@ mod tests {
 
     #[test]
     fn challange1_example() {
-        let data = parse(EXAMPLE_TEXT);
+        let data = parse(EXAMPLE_TEXT).unwrap();
         assert_eq!(challange1(&data), 21);
     }
 
     #[test]
     fn challange2_example() {
-        let data = parse(EXAMPLE_TEXT);
+        let data = parse(EXAMPLE_TEXT).unwrap();
         assert_eq!(challange2(&data), 8);
     }
 }
@@ -15,6 +15,10 @@
  * along with this program.  If not, see <http://www.gnu.org/licenses/>.
  */
 
+use crate::{
+    parse::{lines, Line, ParseError},
+    solution::{Answer, Solution, SolveError},
+};
 use std::collections::HashSet;
 
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
@@ -33,20 +37,22 @@ pub struct Movement {
     x: i32,
     y: i32,
 }
-pub fn parse(text: &str) -> Vec<Movement> {
-    text.lines()
-        .map(|line| {
-            let (direction, amount) = line.split_once(' ').unwrap();
-            let amount = amount.parse().unwrap();
-            match direction {
-                "R" => Movement { x: amount, y: 0 },
-                "L" => Movement { x: -amount, y: 0 },
-                "U" => Movement { y: amount, x: 0 },
-                "D" => Movement { y: -amount, x: 0 },
-                _ => panic!(),
-            }
-        })
-        .collect()
+pub fn parse(text: &str) -> Result<Vec<Movement>, ParseError> {
+    lines(text).map(parse_movement).collect()
+}
+
+fn parse_movement(line: Line) -> Result<Movement, ParseError> {
+    let mut words = line.words();
+    let direction = words.next("a direction")?;
+    let amount = words.parse("an amount of steps")?;
+    words.end()?;
+    match direction {
+        "R" => Ok(Movement { x: amount, y: 0 }),
+        "L" => Ok(Movement { x: -amount, y: 0 }),
+        "U" => Ok(Movement { y: amount, x: 0 }),
+        "D" => Ok(Movement { y: -amount, x: 0 }),
+        _ => Err(line.error(direction, "a direction (`R`, `L`, `U` or `D`)")),
+    }
 }
 
 impl Rope {
@@ -154,6 +160,26 @@ pub fn challange2(movements: &[Movement]) -> usize {
     tail_positions.len()
 }
 
+pub struct Day09;
+
+impl Solution for Day09 {
+    const DAY: u8 = 9;
+
+    type Input = Vec<Movement>;
+
+    fn parse(text: &str) -> Result<Self::Input, ParseError> {
+        parse(text)
+    }
+
+    fn challange1(input: &Self::Input) -> Result<Answer, SolveError> {
+        Ok(challange1(input).into())
+    }
+
+    fn challange2(input: &Self::Input) -> Result<Answer, SolveError> {
+        Ok(challange2(input).into())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -180,7 +206,7 @@ U 20";
 
     #[test]
     fn parse_example() {
-        let data = parse(EXAMPLE_TEXT);
+        let data = parse(EXAMPLE_TEXT).unwrap();
         assert_eq!(
             data,
             vec![
@@ -196,21 +222,30 @@ U 20";
         );
     }
 
+    #[test]
+    fn parse_invalid() {
+        let error = parse("R 4\nX 1").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 2, column 1: expected a direction (`R`, `L`, `U` or `D`), found `X`"
+        );
+    }
+
     #[test]
     fn challange1_example() {
-        let data = parse(EXAMPLE_TEXT);
+        let data = parse(EXAMPLE_TEXT).unwrap();
         assert_eq!(challange1(&data), 13);
     }
 
     #[test]
     fn challange2_example() {
-        let data = parse(EXAMPLE_TEXT);
+        let data = parse(EXAMPLE_TEXT).unwrap();
         assert_eq!(challange2(&data), 1);
     }
 
     #[test]
     fn challange2_example2() {
-        let data = parse(EXAMPLE2_TEXT);
+        let data = parse(EXAMPLE2_TEXT).unwrap();
         assert_eq!(challange2(&data), 36);
     }
 }
@@ -15,6 +15,10 @@
  * along with this program.  If not, see <http://www.gnu.org/licenses/>.
  */
 
+use crate::{
+    parse::{lines, Line, ParseError},
+    solution::{Answer, Solution, SolveError},
+};
 use std::collections::VecDeque;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -23,17 +27,25 @@ pub enum Instruction {
     AddX(i32),
 }
 
-pub fn parse(text: &str) -> Vec<Instruction> {
-    text.lines().map(parse_instruction).collect()
+pub fn parse(text: &str) -> Result<Vec<Instruction>, ParseError> {
+    let instructions = lines(text)
+        .map(parse_instruction)
+        .collect::<Result<Vec<_>, _>>()?;
+    if instructions.is_empty() {
+        return Err(ParseError::end_of_input(text, "an instruction"));
+    }
+    Ok(instructions)
 }
 
-pub fn parse_instruction(text: &str) -> Instruction {
-    if text == "noop" {
-        Instruction::Noop
-    } else {
-        let (_instruction_text, value_text) = text.split_once(' ').unwrap();
-        Instruction::AddX(value_text.parse().unwrap())
-    }
+pub fn parse_instruction(line: Line) -> Result<Instruction, ParseError> {
+    let mut words = line.words();
+    let instruction = match words.next("an instruction")? {
+        "noop" => Instruction::Noop,
+        "addx" => Instruction::AddX(words.parse("a value")?),
+        other => return Err(line.error(other, "an instruction (`noop` or `addx`)")),
+    };
+    words.end()?;
+    Ok(instruction)
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -61,8 +73,9 @@ impl Processor {
                 Instruction::AddX(value) => self.x_register += value,
             }
         } else {
-            let next_instruction = self.instructions_to_go.pop_front().unwrap();
-            match next_instruction {
+            // The processor idles once the program is done.
+            let next_instruction = self.instructions_to_go.pop_front();
+            match next_instruction.unwrap_or(Instruction::Noop) {
                 Instruction::Noop => {}
                 Instruction::AddX(value) => {
                     self.instruction_in_progress = Some(Instruction::AddX(value));
@@ -113,6 +126,26 @@ pub fn challange2(instructions: &[Instruction]) -> String {
     result
 }
 
+pub struct Day10;
+
+impl Solution for Day10 {
+    const DAY: u8 = 10;
+
+    type Input = Vec<Instruction>;
+
+    fn parse(text: &str) -> Result<Self::Input, ParseError> {
+        parse(text)
+    }
+
+    fn challange1(input: &Self::Input) -> Result<Answer, SolveError> {
+        Ok(challange1(input).into())
+    }
+
+    fn challange2(input: &Self::Input) -> Result<Answer, SolveError> {
+        Ok(challange2(input).into())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -272,7 +305,7 @@ noop";
 
     #[test]
     fn parse_example() {
-        let data = parse(EXAMPLE_TEXT);
+        let data = parse(EXAMPLE_TEXT).unwrap();
         assert_eq!(
             data,
             vec![
@@ -283,9 +316,25 @@ noop";
         );
     }
 
+    #[test]
+    fn parse_invalid() {
+        let error = parse("noop\naddx\n").unwrap_err();
+        assert_eq!(error.to_string(), "line 2, column 5: expected a value");
+        let error = parse("").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 1, column 1: expected an instruction"
+        );
+        let error = parse("mulx 3").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 1, column 1: expected an instruction (`noop` or `addx`), found `mulx`"
+        );
+    }
+
     #[test]
     fn processor_example() {
-        let data = parse(EXAMPLE_TEXT);
+        let data = parse(EXAMPLE_TEXT).unwrap();
         let mut processor = Processor::new(&data);
         assert_eq!(processor.x_register(), 1);
         processor.execute_1_cycle();
@@ -302,7 +351,7 @@ noop";
 
     #[test]
     fn processor_example2() {
-        let data = parse(EXAMPLE2_TEXT);
+        let data = parse(EXAMPLE2_TEXT).unwrap();
         let mut processor = Processor::new(&data);
         assert_eq!(processor.x_register(), 1);
         processor.execute_cycles(19);
@@ -319,15 +368,24 @@ noop";
         assert_eq!(processor.x_register(), 18);
     }
 
+    #[test]
+    fn processor_idles_after_program() {
+        let data = parse(EXAMPLE_TEXT).unwrap();
+        let mut processor = Processor::new(&data);
+        processor.execute_cycles(100);
+        assert_eq!(processor.x_register(), -1);
+        assert_eq!(challange1(&data), -720);
+    }
+
     #[test]
     fn challange1_example2() {
-        let data = parse(EXAMPLE2_TEXT);
+        let data = parse(EXAMPLE2_TEXT).unwrap();
         assert_eq!(challange1(&data), 13140);
     }
 
     #[test]
     fn challange2_example2() {
-        let data = parse(EXAMPLE2_TEXT);
+        let data = parse(EXAMPLE2_TEXT).unwrap();
         assert_eq!(
             challange2(&data),
             "\
@@ -15,6 +15,10 @@
  * along with this program.  If not, see <http://www.gnu.org/licenses/>.
  */
 
+use crate::{
+    parse::{paragraphs, Line, ParseError},
+    solution::{Answer, Solution, SolveError},
+};
 use std::{
     cell::RefCell,
     collections::{BTreeMap, VecDeque},
@@ -41,64 +45,106 @@ pub struct Monkey {
     test: Test,
 }
 
-pub fn parse(text: &str) -> Vec<Monkey> {
-    text.split("\n\n").map(parse_monkey).collect()
+const IF_TRUE_PREFIX: &str = "    If true: throw to monkey ";
+const IF_FALSE_PREFIX: &str = "    If false: throw to monkey ";
+
+pub fn parse(text: &str) -> Result<Vec<Monkey>, ParseError> {
+    let paragraphs = paragraphs(text);
+    if paragraphs.is_empty() {
+        return Err(ParseError::end_of_input(text, "a monkey"));
+    }
+    let monkeys = paragraphs
+        .iter()
+        .map(|lines| parse_monkey(lines))
+        .collect::<Result<Vec<_>, _>>()?;
+    if monkeys.len() == 1 {
+        // Monkey business multiplies the inspections of the two most active monkeys.
+        return Err(ParseError::end_of_input(text, "a second monkey"));
+    }
+    for (lines, monkey) in paragraphs.iter().zip(&monkeys) {
+        let targets = [
+            (
+                &lines[4],
+                IF_TRUE_PREFIX,
+                monkey.test.if_true_throw_to_monkey,
+            ),
+            (
+                &lines[5],
+                IF_FALSE_PREFIX,
+                monkey.test.if_false_throw_to_monkey,
+            ),
+        ];
+        for (line, prefix, target) in targets {
+            if target >= monkeys.len() {
+                let expected = format!("a monkey number below {}", monkeys.len());
+                return Err(line.error(line.strip_prefix(prefix)?, expected));
+            }
+        }
+    }
+    Ok(monkeys)
 }
 
-pub fn parse_monkey(text: &str) -> Monkey {
-    let mut lines = text.lines();
-    let _header = lines.next().unwrap();
-    let starting_items = parse_starting_items(lines.next().unwrap());
-    let operation = parse_operation(lines.next().unwrap());
+pub fn parse_monkey(lines: &[Line]) -> Result<Monkey, ParseError> {
+    let end = lines.last().map_or(1, |line| line.number() + 1);
+    let mut lines = lines.iter();
+    let mut next_line = |expected: &str| {
+        lines
+            .next()
+            .ok_or_else(|| ParseError::new(end, 1, expected))
+    };
+    let _header = next_line("a monkey header")?.strip_prefix("Monkey ")?;
+    let starting_items = parse_starting_items(next_line("the starting items")?)?;
+    let operation = parse_operation(next_line("an operation")?)?;
     let test = parse_test(
-        lines.next().unwrap(),
-        lines.next().unwrap(),
-        lines.next().unwrap(),
-    );
-    Monkey {
+        next_line("a test")?,
+        next_line("a monkey to throw to if true")?,
+        next_line("a monkey to throw to if false")?,
+    )?;
+    Ok(Monkey {
         items: VecDeque::from(starting_items).into(),
         operation,
         test,
-    }
+    })
 }
 
-pub fn parse_starting_items(text: &str) -> Vec<usize> {
-    let (_header, items) = text.split_once(": ").unwrap();
-    items.split(", ").map(|t| t.parse().unwrap()).collect()
+pub fn parse_starting_items(line: &Line) -> Result<Vec<usize>, ParseError> {
+    let items = line.strip_prefix("  Starting items: ")?;
+    items
+        .split(", ")
+        .map(|t| line.parse(t, "a worry level"))
+        .collect()
 }
 
-pub fn parse_operation(text: &str) -> Operation {
-    let (_header, operation) = text.split_once(": ").unwrap();
-    let end = operation.strip_prefix("new = old ").unwrap();
+pub fn parse_operation(line: &Line) -> Result<Operation, ParseError> {
+    let end = line.strip_prefix("  Operation: new = old ")?;
     if end == "* old" {
-        Operation::OldMultiplyOld
-    } else if end.starts_with('*') {
-        let value = end.strip_prefix("* ").unwrap();
-        Operation::OldMultiplyValue(value.parse().unwrap())
+        Ok(Operation::OldMultiplyOld)
+    } else if let Some(value) = end.strip_prefix("* ") {
+        Ok(Operation::OldMultiplyValue(line.parse(value, "a value")?))
+    } else if let Some(value) = end.strip_prefix("+ ") {
+        Ok(Operation::OldAddValue(line.parse(value, "a value")?))
     } else {
-        let value = end.strip_prefix("+ ").unwrap();
-        Operation::OldAddValue(value.parse().unwrap())
+        Err(line.error(end, "an operation (`* old`, `* <value>` or `+ <value>`)"))
     }
 }
 
-pub fn parse_test(text_divisible: &str, text_if_true: &str, text_if_false: &str) -> Test {
-    Test {
-        divisible_by: text_divisible
-            .strip_prefix("  Test: divisible by ")
-            .unwrap()
-            .parse()
-            .unwrap(),
-        if_true_throw_to_monkey: text_if_true
-            .strip_prefix("    If true: throw to monkey ")
-            .unwrap()
-            .parse()
-            .unwrap(),
-        if_false_throw_to_monkey: text_if_false
-            .strip_prefix("    If false: throw to monkey ")
-            .unwrap()
-            .parse()
-            .unwrap(),
+pub fn parse_test(
+    line_divisible: &Line,
+    line_if_true: &Line,
+    line_if_false: &Line,
+) -> Result<Test, ParseError> {
+    let divisible_by = line_divisible.strip_prefix("  Test: divisible by ")?;
+    let if_true = line_if_true.strip_prefix(IF_TRUE_PREFIX)?;
+    let if_false = line_if_false.strip_prefix(IF_FALSE_PREFIX)?;
+    let divisor = line_divisible.parse(divisible_by, "a divisor")?;
+    if divisor == 0 {
+        return Err(line_divisible.error(divisible_by, "a divisor above 0"));
     }
+    Ok(Test {
+        divisible_by: divisor,
+        if_true_throw_to_monkey: line_if_true.parse(if_true, "a monkey number")?,
+        if_false_throw_to_monkey: line_if_false.parse(if_false, "a monkey number")?,
+    })
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -146,7 +192,7 @@ impl Operation {
 
 impl Test {
     fn test(&self, item: usize) -> usize {
-        if item % self.divisible_by == 0 {
+        if item.is_multiple_of(self.divisible_by) {
             self.if_true_throw_to_monkey
         } else {
             self.if_false_throw_to_monkey
@@ -203,6 +249,26 @@ pub fn challange2(monkeys: &[Monkey]) -> usize {
     business[0] * business[1]
 }
 
+pub struct Day11;
+
+impl Solution for Day11 {
+    const DAY: u8 = 11;
+
+    type Input = Vec<Monkey>;
+
+    fn parse(text: &str) -> Result<Self::Input, ParseError> {
+        parse(text)
+    }
+
+    fn challange1(input: &Self::Input) -> Result<Answer, SolveError> {
+        Ok(challange1(input).into())
+    }
+
+    fn challange2(input: &Self::Input) -> Result<Answer, SolveError> {
+        Ok(challange2(input).into())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -238,7 +304,7 @@ Monkey 3:
 
     #[test]
     fn parse_example() {
-        let data = parse(EXAMPLE_TEXT);
+        let data = parse(EXAMPLE_TEXT).unwrap();
         assert_eq!(
             data,
             vec![
@@ -284,7 +350,7 @@ Monkey 3:
 
     #[test]
     fn round1_example() {
-        let monkeys = parse(EXAMPLE_TEXT);
+        let monkeys = parse(EXAMPLE_TEXT).unwrap();
         let throws = monkeys[0].execute_turn(None);
         assert_eq!(
             throws,
@@ -382,15 +448,61 @@ Monkey 3:
         }
     }
 
+    #[test]
+    fn parse_invalid() {
+        let text = EXAMPLE_TEXT.replacen("If false: throw to monkey 3", "If false: throw to 3", 1);
+        let error = parse(&text).unwrap_err();
+        assert_eq!(error.line(), 6);
+        assert_eq!(error.column(), 1);
+        assert_eq!(error.expected(), "`If false: throw to monkey`");
+
+        let text = EXAMPLE_TEXT.replacen("new = old * 19", "new = old / 19", 1);
+        let error = parse(&text).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 3, column 24: expected an operation (`* old`, `* <value>` or `+ <value>`), found `/ 19`"
+        );
+
+        let error = parse("Monkey 0:\n  Starting items: 79, 98").unwrap_err();
+        assert_eq!(error.to_string(), "line 3, column 1: expected an operation");
+
+        let text = EXAMPLE_TEXT.replacen(
+            "If true: throw to monkey 2",
+            "If true: throw to monkey 5",
+            1,
+        );
+        let error = parse(&text).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 5, column 30: expected a monkey number below 4, found `5`"
+        );
+        let error = parse("").unwrap_err();
+        assert_eq!(error.to_string(), "line 1, column 1: expected a monkey");
+
+        let text = EXAMPLE_TEXT.replacen("divisible by 23", "divisible by 0", 1);
+        let error = parse(&text).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 4, column 22: expected a divisor above 0, found `0`"
+        );
+
+        let text = EXAMPLE_TEXT.split("\n\n").next().unwrap();
+        let error = parse(text).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 7, column 1: expected a second monkey"
+        );
+    }
+
     #[test]
     fn challange1_example() {
-        let data = parse(EXAMPLE_TEXT);
+        let data = parse(EXAMPLE_TEXT).unwrap();
         assert_eq!(challange1(&data), 10605);
     }
 
     #[test]
     fn challange2_example() {
-        let data = parse(EXAMPLE_TEXT);
+        let data = parse(EXAMPLE_TEXT).unwrap();
         assert_eq!(challange2(&data), 2713310158);
     }
 }
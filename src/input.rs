@@ -0,0 +1,143 @@
+/* Copyright (C) 2022 Casper Meijn <casper@meijn.net>
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use std::{
+    env, error, fmt, fs,
+    io::{self, Read},
+    path::{Path, PathBuf},
+};
+
+/// Environment variable that overrides the directory inputs are loaded from.
+pub const INPUT_DIR_VAR: &str = "AOC_INPUT_DIR";
+
+/// Directory that is used when no other directory is configured.
+pub const DEFAULT_INPUT_DIR: &str = "data";
+
+#[derive(Debug)]
+pub enum InputError {
+    Missing { day: u8, path: PathBuf },
+    Io { path: PathBuf, error: io::Error },
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputError::Missing { day, path } => {
+                write!(f, "no input for day {day}: `{}` not found", path.display())
+            }
+            InputError::Io { path, error } => {
+                write!(f, "failed to read `{}`: {error}", path.display())
+            }
+        }
+    }
+}
+
+impl error::Error for InputError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            InputError::Missing { .. } => None,
+            InputError::Io { error, .. } => Some(error),
+        }
+    }
+}
+
+/// Directory containing the puzzle inputs, named `day01.txt`, `day02.txt`, etc.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InputDir {
+    path: PathBuf,
+}
+
+impl Default for InputDir {
+    fn default() -> Self {
+        Self::new(DEFAULT_INPUT_DIR)
+    }
+}
+
+impl InputDir {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Uses the directory from [`INPUT_DIR_VAR`] if it is set, else [`DEFAULT_INPUT_DIR`].
+    pub fn from_env() -> Self {
+        env::var_os(INPUT_DIR_VAR).map_or_else(Self::default, Self::new)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn path_for(&self, day: u8) -> PathBuf {
+        self.path.join(format!("day{day:02}.txt"))
+    }
+
+    pub fn load(&self, day: u8) -> Result<String, InputError> {
+        let path = self.path_for(day);
+        match fs::read_to_string(&path) {
+            Err(error) if error.kind() == io::ErrorKind::NotFound => {
+                Err(InputError::Missing { day, path })
+            }
+            result => result.map_err(|error| InputError::Io { path, error }),
+        }
+    }
+}
+
+/// Reads an input from a file, or from stdin if `path` is `-`.
+pub fn load_file(path: impl AsRef<Path>) -> Result<String, InputError> {
+    let path = path.as_ref();
+    let result = if path == Path::new("-") {
+        let mut text = String::new();
+        io::stdin().read_to_string(&mut text).map(|_| text)
+    } else {
+        fs::read_to_string(path)
+    };
+    result.map_err(|error| InputError::Io {
+        path: path.to_path_buf(),
+        error,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn path_for() {
+        let dir = InputDir::new("inputs");
+        assert_eq!(dir.path_for(7), Path::new("inputs/day07.txt"));
+        assert_eq!(dir.path_for(11), Path::new("inputs/day11.txt"));
+    }
+
+    #[test]
+    fn load() {
+        let path = env::temp_dir().join("advent-of-code-2022-input-test");
+        fs::create_dir_all(&path).unwrap();
+        fs::write(path.join("day03.txt"), "vJrwpWtwJgWrhcsFMMfFFhFp\n").unwrap();
+
+        let dir = InputDir::new(&path);
+        assert_eq!(dir.load(3).unwrap(), "vJrwpWtwJgWrhcsFMMfFFhFp\n");
+        let error = dir.load(4).unwrap_err();
+        assert!(matches!(error, InputError::Missing { day: 4, .. }));
+        assert_eq!(
+            error.to_string(),
+            format!(
+                "no input for day 4: `{}` not found",
+                path.join("day04.txt").display()
+            )
+        );
+    }
+}
@@ -0,0 +1,336 @@
+/* Copyright (C) 2022 Casper Meijn <casper@meijn.net>
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+//! Intervals of section numbers and sets of them.
+
+use std::{fmt, ops::RangeInclusive};
+
+/// A non-empty, inclusive range of section numbers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Interval {
+    start: u32,
+    end: u32,
+}
+
+impl Interval {
+    /// Creates the interval `start..=end`.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `start` is larger than `end`.
+    pub fn new(start: u32, end: u32) -> Self {
+        assert!(start <= end, "interval {start}-{end} is reversed");
+        Self { start, end }
+    }
+
+    pub fn start(&self) -> u32 {
+        self.start
+    }
+
+    pub fn end(&self) -> u32 {
+        self.end
+    }
+
+    /// Number of sections in the interval, which is never zero.
+    pub fn length(&self) -> u64 {
+        u64::from(self.end - self.start) + 1
+    }
+
+    pub fn contains(&self, section: u32) -> bool {
+        self.start <= section && section <= self.end
+    }
+
+    /// Returns whether every section of `other` is also in `self`.
+    pub fn contains_interval(&self, other: &Interval) -> bool {
+        self.start <= other.start && other.end <= self.end
+    }
+
+    pub fn overlaps(&self, other: &Interval) -> bool {
+        self.start <= other.end && other.start <= self.end
+    }
+
+    /// Returns whether the intervals overlap or are directly next to each other.
+    fn touches(&self, other: &Interval) -> bool {
+        u64::from(self.start) <= u64::from(other.end) + 1
+            && u64::from(other.start) <= u64::from(self.end) + 1
+    }
+
+    pub fn intersection(&self, other: &Interval) -> Option<Interval> {
+        self.overlaps(other)
+            .then(|| Interval::new(self.start.max(other.start), self.end.min(other.end)))
+    }
+
+    /// Returns the sections in either interval, as long as that is a single interval.
+    pub fn union(&self, other: &Interval) -> Option<Interval> {
+        self.touches(other)
+            .then(|| Interval::new(self.start.min(other.start), self.end.max(other.end)))
+    }
+
+    /// Returns the sections in `self` that are not in `other`.
+    pub fn difference(&self, other: &Interval) -> IntervalSet {
+        IntervalSet::from(*self).difference(&IntervalSet::from(*other))
+    }
+}
+
+impl From<RangeInclusive<u32>> for Interval {
+    fn from(range: RangeInclusive<u32>) -> Self {
+        Interval::new(*range.start(), *range.end())
+    }
+}
+
+impl From<Interval> for RangeInclusive<u32> {
+    fn from(interval: Interval) -> Self {
+        interval.start..=interval.end
+    }
+}
+
+impl fmt::Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}", self.start, self.end)
+    }
+}
+
+/// A set of sections, stored as sorted intervals that neither overlap nor touch.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct IntervalSet {
+    intervals: Vec<Interval>,
+}
+
+impl IntervalSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the merged intervals in order.
+    pub fn intervals(&self) -> &[Interval] {
+        &self.intervals
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// Number of sections in the set.
+    pub fn len(&self) -> u64 {
+        self.intervals.iter().map(Interval::length).sum()
+    }
+
+    pub fn contains(&self, section: u32) -> bool {
+        let index = self
+            .intervals
+            .partition_point(|interval| interval.end < section);
+        self.intervals
+            .get(index)
+            .is_some_and(|interval| interval.contains(section))
+    }
+
+    /// Returns the smallest interval that contains the whole set.
+    pub fn span(&self) -> Option<Interval> {
+        let first = self.intervals.first()?;
+        let last = self.intervals.last()?;
+        Some(Interval::new(first.start, last.end))
+    }
+
+    pub fn insert(&mut self, interval: Interval) {
+        let start = self
+            .intervals
+            .partition_point(|other| !other.touches(&interval) && other.end < interval.start);
+        let end = start
+            + self.intervals[start..]
+                .iter()
+                .take_while(|other| other.touches(&interval))
+                .count();
+        let merged = self.intervals[start..end]
+            .iter()
+            .fold(interval, |merged, other| merged.union(other).unwrap());
+        self.intervals.splice(start..end, [merged]);
+    }
+
+    pub fn union(&self, other: &IntervalSet) -> IntervalSet {
+        self.intervals
+            .iter()
+            .chain(&other.intervals)
+            .copied()
+            .collect()
+    }
+
+    pub fn intersection(&self, other: &IntervalSet) -> IntervalSet {
+        let mut result = IntervalSet::new();
+        let (mut i, mut j) = (0, 0);
+        while let (Some(a), Some(b)) = (self.intervals.get(i), other.intervals.get(j)) {
+            if let Some(overlap) = a.intersection(b) {
+                result.intervals.push(overlap);
+            }
+            if a.end < b.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        result
+    }
+
+    /// Returns the sections in `self` that are not in `other`.
+    pub fn difference(&self, other: &IntervalSet) -> IntervalSet {
+        let mut result = IntervalSet::new();
+        let mut removed = other.intervals.iter().peekable();
+        for interval in &self.intervals {
+            let mut start = Some(interval.start);
+            while let (Some(first), Some(hole)) = (start, removed.peek()) {
+                if hole.end < first {
+                    removed.next();
+                    continue;
+                }
+                if hole.start > interval.end {
+                    break;
+                }
+                if hole.start > first {
+                    result.intervals.push(Interval::new(first, hole.start - 1));
+                }
+                start = hole.end.checked_add(1).filter(|&next| next <= interval.end);
+                if hole.end > interval.end {
+                    break;
+                }
+                removed.next();
+            }
+            if let Some(first) = start {
+                result.intervals.push(Interval::new(first, interval.end));
+            }
+        }
+        result
+    }
+
+    /// Returns the sections within `bounds` that are not in the set.
+    pub fn gaps(&self, bounds: Interval) -> IntervalSet {
+        IntervalSet::from(bounds).difference(self)
+    }
+}
+
+impl From<Interval> for IntervalSet {
+    fn from(interval: Interval) -> Self {
+        Self {
+            intervals: vec![interval],
+        }
+    }
+}
+
+impl FromIterator<Interval> for IntervalSet {
+    fn from_iter<T: IntoIterator<Item = Interval>>(iter: T) -> Self {
+        let mut intervals: Vec<Interval> = iter.into_iter().collect();
+        intervals.sort_unstable();
+        let mut set = IntervalSet::new();
+        for interval in intervals {
+            match set.intervals.last_mut() {
+                Some(last) if last.touches(&interval) => *last = last.union(&interval).unwrap(),
+                _ => set.intervals.push(interval),
+            }
+        }
+        set
+    }
+}
+
+impl fmt::Display for IntervalSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, interval) in self.intervals.iter().enumerate() {
+            if i > 0 {
+                write!(f, ",")?;
+            }
+            write!(f, "{interval}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(intervals: &[RangeInclusive<u32>]) -> IntervalSet {
+        intervals.iter().cloned().map(Interval::from).collect()
+    }
+
+    #[test]
+    fn interval() {
+        let a = Interval::new(2, 6);
+        let b = Interval::from(4..=8);
+        assert_eq!(a.length(), 5);
+        assert!(a.contains(2) && a.contains(6) && !a.contains(7));
+        assert!(a.contains_interval(&Interval::new(3, 4)));
+        assert!(!a.contains_interval(&b));
+        assert_eq!(a.intersection(&b), Some(Interval::new(4, 6)));
+        assert_eq!(a.union(&b), Some(Interval::new(2, 8)));
+        assert_eq!(a.difference(&b), set(&[2..=3]));
+        assert_eq!(b.difference(&Interval::new(5, 6)), set(&[4..=4, 7..=8]));
+
+        let c = Interval::new(7, 9);
+        assert_eq!(a.intersection(&c), None);
+        assert_eq!(a.union(&c), Some(Interval::new(2, 9)));
+        assert_eq!(a.union(&Interval::new(8, 9)), None);
+        assert_eq!(a.to_string(), "2-6");
+        assert_eq!(Interval::new(0, u32::MAX).length(), 1 << 32);
+    }
+
+    #[test]
+    #[should_panic]
+    fn interval_reversed() {
+        Interval::new(6, 4);
+    }
+
+    #[test]
+    fn merge() {
+        let merged = set(&[6..=8, 2..=4, 5..=5, 11..=12, 10..=10, 14..=20, 15..=16]);
+        assert_eq!(merged.to_string(), "2-8,10-12,14-20");
+        assert_eq!(merged.len(), 17);
+        assert!(merged.contains(11));
+        assert!(!merged.contains(13));
+        assert_eq!(merged.span(), Some(Interval::new(2, 20)));
+
+        let mut inserted = IntervalSet::new();
+        for interval in [
+            6..=8,
+            2..=4,
+            5..=5,
+            11..=12,
+            10..=10,
+            14..=20,
+            15..=16,
+            9..=9,
+        ] {
+            inserted.insert(interval.into());
+        }
+        assert_eq!(inserted, set(&[2..=12, 14..=20]));
+    }
+
+    #[test]
+    fn set_operations() {
+        let a = set(&[2..=8, 12..=15, 20..=25]);
+        let b = set(&[0..=3, 7..=13, 22..=22, 30..=31]);
+        assert_eq!(a.union(&b).to_string(), "0-15,20-25,30-31");
+        assert_eq!(a.intersection(&b).to_string(), "2-3,7-8,12-13,22-22");
+        assert_eq!(a.difference(&b).to_string(), "4-6,14-15,20-21,23-25");
+        assert_eq!(b.difference(&a).to_string(), "0-1,9-11,30-31");
+        assert_eq!(
+            a.gaps(Interval::new(0, 30)).to_string(),
+            "0-1,9-11,16-19,26-30"
+        );
+        assert!(a.difference(&a).is_empty());
+        assert_eq!(
+            set(&[0..=u32::MAX]).difference(&set(&[5..=u32::MAX])),
+            set(&[0..=4])
+        );
+    }
+}
@@ -15,6 +15,8 @@
  * along with this program.  If not, see <http://www.gnu.org/licenses/>.
  */
 
+pub mod answers;
+pub mod bench;
 pub mod day01;
 pub mod day02;
 pub mod day03;
@@ -26,3 +28,31 @@ pub mod day08;
 pub mod day09;
 pub mod day10;
 pub mod day11;
+pub mod input;
+pub mod interval;
+pub mod parse;
+pub mod report;
+pub mod runner;
+pub mod solution;
+
+use solution::DynSolution;
+
+/// All days that are solved, in order.
+pub static DAYS: [&dyn DynSolution; 11] = [
+    &day01::Day01,
+    &day02::Day02,
+    &day03::Day03,
+    &day04::Day04,
+    &day05::Day05,
+    &day06::Day06,
+    &day07::Day07,
+    &day08::Day08,
+    &day09::Day09,
+    &day10::Day10,
+    &day11::Day11,
+];
+
+/// Looks up the solution of a day by its number.
+pub fn day(number: u8) -> Option<&'static dyn DynSolution> {
+    DAYS.iter().copied().find(|day| day.day() == number)
+}
@@ -15,84 +15,280 @@
  * along with this program.  If not, see <http://www.gnu.org/licenses/>.
  */
 
-use advent_of_code_2022::*;
+use advent_of_code_2022::{
+    answers::{self, Verdict, ANSWERS_FILE},
+    input::{self, InputDir},
+    report::Format,
+    *,
+};
+use std::{
+    env, process,
+    time::{Duration, Instant},
+};
+
+const USAGE: &str = "\
+Usage: advent-of-code-2022 [OPTIONS]
+
+Options:
+  -d, --day <DAY>          Day to solve, may be repeated or comma separated (default: all)
+  -p, --part <PART>        Challange to solve, 1 or 2, may be repeated (default: both)
+  -i, --input <PATH>       Input file for the selected day, use `-` for stdin
+  -D, --input-dir <DIR>    Directory with `dayNN.txt` inputs (default: $AOC_INPUT_DIR or `data`)
+  -f, --format <FORMAT>    Output format of the answers: text, json or csv (default: text)
+  -j, --jobs <N>           Number of days to solve in parallel (default: 1)
+  -v, --verify             Check the answers against `answers.txt` in the input directory
+  -b, --bench              Time parsing and both challanges instead of printing answers
+  -n, --iterations <N>     Number of benchmark runs per day (default: 10)
+  -h, --help               Print this help";
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+struct Options {
+    days: Vec<u8>,
+    parts: Vec<u8>,
+    input: Option<String>,
+    input_dir: Option<String>,
+    format: Format,
+    jobs: usize,
+    verify: bool,
+    bench: bool,
+    iterations: usize,
+    help: bool,
+}
+
+fn last_day() -> u8 {
+    DAYS.last().unwrap().day()
+}
+
+fn parse_list(text: &str, name: &str, max: u8) -> Result<Vec<u8>, String> {
+    text.split(',')
+        .map(|value| match value.trim().parse() {
+            Ok(number) if (1..=max).contains(&number) => Ok(number),
+            _ => Err(format!("invalid {name} `{value}`, expected 1 to {max}")),
+        })
+        .collect()
+}
+
+fn parse_args(args: impl IntoIterator<Item = String>) -> Result<Options, String> {
+    let mut options = Options {
+        jobs: 1,
+        iterations: 10,
+        ..Default::default()
+    };
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| {
+            args.next()
+                .ok_or_else(|| format!("missing value for `{name}`"))
+        };
+        match arg.as_str() {
+            "-d" | "--day" => options
+                .days
+                .extend(parse_list(&value(&arg)?, "day", last_day())?),
+            "-p" | "--part" => options.parts.extend(parse_list(&value(&arg)?, "part", 2)?),
+            "-i" | "--input" => options.input = Some(value(&arg)?),
+            "-D" | "--input-dir" => options.input_dir = Some(value(&arg)?),
+            "-j" | "--jobs" => {
+                let text = value(&arg)?;
+                options.jobs = match text.parse() {
+                    Ok(jobs) if jobs > 0 => jobs,
+                    _ => return Err(format!("invalid number of jobs `{text}`")),
+                }
+            }
+            "-f" | "--format" => options.format = value(&arg)?.parse()?,
+            "-v" | "--verify" => options.verify = true,
+            "-b" | "--bench" => options.bench = true,
+            "-n" | "--iterations" => {
+                let text = value(&arg)?;
+                options.iterations = match text.parse() {
+                    Ok(iterations) if iterations > 0 => iterations,
+                    _ => return Err(format!("invalid iterations `{text}`")),
+                }
+            }
+            "-h" | "--help" => options.help = true,
+            _ => return Err(format!("unexpected argument `{arg}`")),
+        }
+    }
+
+    if options.days.is_empty() {
+        options.days = DAYS.iter().map(|day| day.day()).collect();
+    }
+    if options.parts.is_empty() {
+        options.parts = vec![1, 2];
+    }
+    options.days.sort_unstable();
+    options.days.dedup();
+    options.parts.sort_unstable();
+    options.parts.dedup();
+    if options.verify && options.bench {
+        return Err("`--verify` and `--bench` can't be combined".to_string());
+    }
+    if options.input.is_some() && options.days.len() != 1 {
+        return Err("`--input` requires exactly one `--day`".to_string());
+    }
+    Ok(options)
+}
 
 fn main() {
-    let text = include_str!("../data/day01.txt");
-    let elves = day01::parse(text);
-    let challange1 = day01::challange1(&elves);
-    println!("Day 1, challange 1: {challange1}");
-    let challange2 = day01::challange2(&elves);
-    println!("Day 1, challange 2: {challange2}");
-
-    let text = include_str!("../data/day02.txt");
-    let data = day02::challange1::parse(text);
-    let challange1 = day02::challange1::challange1(&data);
-    println!("Day 2, challange 1: {challange1}");
-    let data = day02::challange2::parse(text);
-    let challange2 = day02::challange2::challange2(&data);
-    println!("Day 2, challange 2: {challange2}");
-
-    let text = include_str!("../data/day03.txt");
-    let data = day03::parse(text);
-    let challange1 = day03::challange1(&data);
-    println!("Day 3, challange 1: {challange1}");
-    let challange2 = day03::challange2(&data);
-    println!("Day 3, challange 2: {challange2}");
-
-    let text = include_str!("../data/day04.txt");
-    let data = day04::parse(text);
-    let challange1 = day04::challange1(&data);
-    println!("Day 4, challange 1: {challange1}");
-    let challange1 = day04::challange2(&data);
-    println!("Day 4, challange 2: {challange1}");
-
-    let text = include_str!("../data/day05.txt");
-    let data = day05::parse(text);
-    let challange1 = day05::challange1(data.clone());
-    println!("Day 5, challange 1: {challange1}");
-    let challange1 = day05::challange2(data);
-    println!("Day 5, challange 2: {challange1}");
-
-    let text = include_str!("../data/day06.txt");
-    let data = day06::parse(text);
-    let challange1 = day06::challange1(&data);
-    println!("Day 6, challange 1: {challange1}");
-    let challange2 = day06::challange2(&data);
-    println!("Day 6, challange 2: {challange2}");
-
-    let text = include_str!("../data/day07.txt");
-    let data = day07::parse(text);
-    let challange1 = day07::challange1(&data);
-    println!("Day 7, challange 1: {challange1}");
-    let challange2 = day07::challange2(&data);
-    println!("Day 7, challange 2: {challange2}");
-
-    let text = include_str!("../data/day08.txt");
-    let data = day08::parse(text);
-    let challange1 = day08::challange1(&data);
-    println!("Day 8, challange 1: {challange1}");
-    let challange2 = day08::challange2(&data);
-    println!("Day 8, challange 2: {challange2}");
-
-    let text = include_str!("../data/day09.txt");
-    let data = day09::parse(text);
-    let challange1 = day09::challange1(&data);
-    println!("Day 9, challange 1: {challange1}");
-    let challange2 = day09::challange2(&data);
-    println!("Day 9, challange 2: {challange2}");
-
-    let text = include_str!("../data/day10.txt");
-    let data = day10::parse(text);
-    let challange1 = day10::challange1(&data);
-    println!("Day 10, challange 1: {challange1}");
-    let challange2 = day10::challange2(&data);
-    println!("Day 10, challange 2: \n{challange2}");
-
-    let text = include_str!("../data/day11.txt");
-    let data = day11::parse(text);
-    let challange1 = day11::challange1(&data);
-    println!("Day 11, challange 1: {challange1}");
-    let challange2 = day11::challange2(&data);
-    println!("Day 11, challange 2: {challange2}");
+    let options = match parse_args(env::args().skip(1)) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("error: {message}\n\n{USAGE}");
+            process::exit(2);
+        }
+    };
+    if options.help {
+        println!("{USAGE}");
+        return;
+    }
+
+    let input_dir = options
+        .input_dir
+        .as_ref()
+        .map_or_else(InputDir::from_env, InputDir::new);
+    let mut failed = false;
+    let mut jobs = Vec::new();
+    if options.bench {
+        println!("{}", bench::DayBenchmark::HEADER);
+    }
+    for &number in &options.days {
+        let day = day(number).unwrap();
+        let text = match &options.input {
+            Some(path) => input::load_file(path),
+            None => input_dir.load(number),
+        };
+        let text = match text {
+            Ok(text) => text,
+            Err(error) => {
+                eprintln!("error: {error}");
+                failed = true;
+                continue;
+            }
+        };
+        if options.bench {
+            match bench::bench(day, &text, options.iterations) {
+                Ok(benchmark) => println!("{benchmark}"),
+                Err(error) => {
+                    eprintln!("error: day {number}: {error}");
+                    failed = true;
+                }
+            }
+            continue;
+        }
+        jobs.push(runner::Job { day, text });
+    }
+
+    let start = Instant::now();
+    let job_results = runner::solve_jobs(&jobs, &options.parts, options.jobs);
+    let wall_clock = start.elapsed();
+    let cpu_time = job_results
+        .iter()
+        .map(|job_result| job_result.cpu_time)
+        .sum::<Option<Duration>>();
+    let mut results = Vec::new();
+    for job_result in job_results {
+        for result in job_result.results {
+            match result {
+                Ok(result) => results.push(result),
+                Err(error) => {
+                    let number = job_result.day;
+                    eprintln!("error: day {number}: {error}");
+                    failed = true;
+                }
+            }
+        }
+    }
+    if options.jobs > 1 {
+        match cpu_time {
+            Some(cpu_time) => {
+                eprintln!("Solved in {wall_clock:.1?} wall-clock, {cpu_time:.1?} summed CPU time")
+            }
+            None => eprintln!("Solved in {wall_clock:.1?} wall-clock"),
+        }
+    }
+
+    if options.verify {
+        let answers_path = input_dir.path().join(ANSWERS_FILE);
+        let answers = input::load_file(&answers_path).map(|text| answers::parse(&text));
+        let answers = match answers {
+            Ok(Ok(answers)) => answers,
+            Ok(Err(error)) => {
+                eprintln!(
+                    "error: failed to parse `{}`: {error}",
+                    answers_path.display()
+                );
+                process::exit(1);
+            }
+            Err(error) => {
+                eprintln!("error: {error}");
+                process::exit(1);
+            }
+        };
+        print!("{}", answers::format_table(&answers, &results));
+        failed |= results
+            .iter()
+            .any(|result| matches!(answers.verify(result), Verdict::Fail { .. }));
+    } else if !options.bench {
+        print!("{}", report::format(&results, options.format));
+    }
+
+    if failed {
+        process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(text: &str) -> Vec<String> {
+        text.split_whitespace().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn parse_args_default() {
+        let options = parse_args(args("")).unwrap();
+        assert_eq!(options.days, (1..=11).collect::<Vec<_>>());
+        assert_eq!(options.parts, vec![1, 2]);
+        assert_eq!(options.input, None);
+    }
+
+    #[test]
+    fn parse_args_selection() {
+        let options = parse_args(args("--day 7 --part 2 -i input.txt")).unwrap();
+        assert_eq!(options.days, vec![7]);
+        assert_eq!(options.parts, vec![2]);
+        assert_eq!(options.input, Some("input.txt".to_string()));
+
+        let options = parse_args(args("--input-dir inputs")).unwrap();
+        assert_eq!(options.input_dir, Some("inputs".to_string()));
+
+        let options = parse_args(args("--jobs 4")).unwrap();
+        assert_eq!(options.jobs, 4);
+
+        let options = parse_args(args("--format csv")).unwrap();
+        assert_eq!(options.format, Format::Csv);
+
+        let options = parse_args(args("--verify")).unwrap();
+        assert!(options.verify);
+
+        let options = parse_args(args("--bench -n 100")).unwrap();
+        assert!(options.bench);
+        assert_eq!(options.iterations, 100);
+
+        let options = parse_args(args("-d 1,3 --day 5")).unwrap();
+        assert_eq!(options.days, vec![1, 3, 5]);
+    }
+
+    #[test]
+    fn parse_args_invalid() {
+        assert!(parse_args(args("--day 12")).is_err());
+        assert!(parse_args(args("--part 3")).is_err());
+        assert!(parse_args(args("--day")).is_err());
+        assert!(parse_args(args("--input -")).is_err());
+        assert!(parse_args(args("--iterations 0")).is_err());
+        assert!(parse_args(args("--verify --bench")).is_err());
+        assert!(parse_args(args("--format xml")).is_err());
+        assert!(parse_args(args("--jobs 0")).is_err());
+        assert!(parse_args(args("--verbose")).is_err());
+    }
 }
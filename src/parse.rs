@@ -0,0 +1,310 @@
+/* Copyright (C) 2022 Casper Meijn <casper@meijn.net>
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use std::{
+    error::Error,
+    fmt, io,
+    str::{FromStr, SplitWhitespace},
+};
+
+/// Error returned when an input text doesn't match the expected format.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    line: usize,
+    column: usize,
+    expected: String,
+    found: Option<String>,
+}
+
+impl ParseError {
+    /// Creates an error at a 1-based line and column, without knowing what was found there.
+    pub fn new(line: usize, column: usize, expected: impl Into<String>) -> Self {
+        Self {
+            line,
+            column,
+            expected: expected.into(),
+            found: None,
+        }
+    }
+
+    /// Creates an error just after the last line of `text`.
+    pub fn end_of_input(text: &str, expected: impl Into<String>) -> Self {
+        Self::new(text.lines().count() + 1, 1, expected)
+    }
+
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
+    pub fn expected(&self) -> &str {
+        &self.expected
+    }
+
+    pub fn found(&self) -> Option<&str> {
+        self.found.as_deref()
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: expected {}",
+            self.line, self.column, self.expected
+        )?;
+        if let Some(found) = &self.found {
+            write!(f, ", found `{found}`")?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for ParseError {}
+
+/// Error returned when parsing while reading an input from a stream.
+#[derive(Debug)]
+pub enum ReadError {
+    Io(io::Error),
+    Parse(ParseError),
+}
+
+impl fmt::Display for ReadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReadError::Io(error) => write!(f, "failed to read input: {error}"),
+            ReadError::Parse(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+impl Error for ReadError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ReadError::Io(error) => Some(error),
+            ReadError::Parse(error) => Some(error),
+        }
+    }
+}
+
+impl From<io::Error> for ReadError {
+    fn from(error: io::Error) -> Self {
+        ReadError::Io(error)
+    }
+}
+
+impl From<ParseError> for ReadError {
+    fn from(error: ParseError) -> Self {
+        ReadError::Parse(error)
+    }
+}
+
+/// A single line of an input text, which knows its own line number.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Line<'a> {
+    number: usize,
+    text: &'a str,
+}
+
+/// Splits `text` into lines, numbered from 1.
+pub fn lines(text: &str) -> impl Iterator<Item = Line<'_>> {
+    text.lines()
+        .enumerate()
+        .map(|(i, text)| Line::new(i + 1, text))
+}
+
+/// Splits `text` into groups of lines, separated by one or more empty lines.
+pub fn paragraphs(text: &str) -> Vec<Vec<Line<'_>>> {
+    let lines = lines(text).collect::<Vec<_>>();
+    lines
+        .split(|line| line.text().is_empty())
+        .filter(|paragraph| !paragraph.is_empty())
+        .map(<[Line]>::to_vec)
+        .collect()
+}
+
+impl<'a> Line<'a> {
+    pub fn new(number: usize, text: &'a str) -> Self {
+        Self { number, text }
+    }
+
+    pub fn number(&self) -> usize {
+        self.number
+    }
+
+    pub fn text(&self) -> &'a str {
+        self.text
+    }
+
+    /// Creates an error pointing at `fragment`, which should be a slice of this line.
+    ///
+    /// An empty fragment points at the position in between characters, in which case the next
+    /// character is reported as found.
+    pub fn error(&self, fragment: &str, expected: impl Into<String>) -> ParseError {
+        let start = self.text.as_ptr() as usize;
+        let offset = (fragment.as_ptr() as usize)
+            .checked_sub(start)
+            .filter(|&offset| offset <= self.text.len())
+            .unwrap_or(0);
+        let before = &self.text[..offset];
+        let found = if fragment.is_empty() {
+            self.text[offset..].chars().next().map(String::from)
+        } else {
+            Some(fragment.to_string())
+        };
+        ParseError {
+            line: self.number,
+            column: before.chars().count() + 1,
+            expected: expected.into(),
+            found,
+        }
+    }
+
+    /// Creates an error pointing just after the last character of this line.
+    pub fn error_at_end(&self, expected: impl Into<String>) -> ParseError {
+        self.error(&self.text[self.text.len()..], expected)
+    }
+
+    /// Strips `prefix` from the start of this line.
+    pub fn strip_prefix(&self, prefix: &str) -> Result<&'a str, ParseError> {
+        self.text
+            .strip_prefix(prefix)
+            .ok_or_else(|| self.error(self.text, format!("`{}`", prefix.trim())))
+    }
+
+    /// Splits `fragment` of this line around the first occurence of `delimiter`.
+    pub fn split_once(
+        &self,
+        fragment: &'a str,
+        delimiter: char,
+    ) -> Result<(&'a str, &'a str), ParseError> {
+        fragment
+            .split_once(delimiter)
+            .ok_or_else(|| self.error(&fragment[fragment.len()..], format!("`{delimiter}`")))
+    }
+
+    /// Parses `fragment` of this line, for example as a number.
+    pub fn parse<T: FromStr>(&self, fragment: &str, expected: &str) -> Result<T, ParseError> {
+        fragment.parse().map_err(|_| self.error(fragment, expected))
+    }
+
+    /// Iterates over the whitespace separated words of this line.
+    pub fn words(&self) -> Words<'a> {
+        Words {
+            line: *self,
+            words: self.text.split_whitespace(),
+        }
+    }
+}
+
+/// Whitespace separated words of a [`Line`], with errors pointing at the offending word.
+#[derive(Clone, Debug)]
+pub struct Words<'a> {
+    line: Line<'a>,
+    words: SplitWhitespace<'a>,
+}
+
+impl<'a> Words<'a> {
+    /// Returns the next word, or an error if the line has ended.
+    pub fn next(&mut self, expected: &str) -> Result<&'a str, ParseError> {
+        self.words
+            .next()
+            .ok_or_else(|| self.line.error_at_end(expected))
+    }
+
+    /// Consumes the next word, which should be exactly `keyword`.
+    pub fn keyword(&mut self, keyword: &str) -> Result<(), ParseError> {
+        let expected = format!("`{keyword}`");
+        let word = self.next(&expected)?;
+        if word == keyword {
+            Ok(())
+        } else {
+            Err(self.line.error(word, expected))
+        }
+    }
+
+    /// Parses the next word, for example as a number.
+    pub fn parse<T: FromStr>(&mut self, expected: &str) -> Result<T, ParseError> {
+        let word = self.next(expected)?;
+        self.line.parse(word, expected)
+    }
+
+    /// Checks that there are no words left on the line.
+    pub fn end(mut self) -> Result<(), ParseError> {
+        match self.words.next() {
+            Some(word) => Err(self.line.error(word, "end of line")),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn error_position() {
+        let line = Line::new(3, "move 1 form 2 to 1");
+        let error = line.error(&line.text()[7..11], "`from`");
+        assert_eq!(error.line(), 3);
+        assert_eq!(error.column(), 8);
+        assert_eq!(error.found(), Some("form"));
+        assert_eq!(
+            error.to_string(),
+            "line 3, column 8: expected `from`, found `form`"
+        );
+
+        let error = line.error_at_end("a number");
+        assert_eq!(error.column(), 19);
+        assert_eq!(error.found(), None);
+        assert_eq!(error.to_string(), "line 3, column 19: expected a number");
+    }
+
+    #[test]
+    fn error_position_unicode() {
+        let line = Line::new(1, "ééé x");
+        let error = line.error(&line.text()[7..8], "`y`");
+        assert_eq!(error.column(), 5);
+    }
+
+    #[test]
+    fn words() {
+        let line = Line::new(1, "move 1 from 2 to 1");
+        let mut words = line.words();
+        assert_eq!(words.keyword("move"), Ok(()));
+        assert_eq!(words.parse::<u32>("a number"), Ok(1));
+        assert_eq!(
+            words.keyword("to").unwrap_err().to_string(),
+            "line 1, column 8: expected `to`, found `from`"
+        );
+        assert_eq!(words.next("a number"), Ok("2"));
+        assert!(words.end().is_err());
+    }
+
+    #[test]
+    fn paragraphs_example() {
+        let paragraphs = paragraphs("1\n2\n\n3\n\n\n4\n");
+        let numbers = paragraphs
+            .iter()
+            .map(|paragraph| paragraph.iter().map(Line::number).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        assert_eq!(numbers, vec![vec![1, 2], vec![4], vec![7]]);
+    }
+}
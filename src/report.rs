@@ -0,0 +1,192 @@
+/* Copyright (C) 2022 Casper Meijn <casper@meijn.net>
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use crate::{runner::PuzzleResult, solution::Answer};
+use std::{fmt::Write, str::FromStr};
+
+/// How the answers are written by the runner.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Format {
+    #[default]
+    Text,
+    Json,
+    Csv,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        match text {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            _ => Err(format!(
+                "invalid format `{text}`, expected `text`, `json` or `csv`"
+            )),
+        }
+    }
+}
+
+pub fn format(results: &[PuzzleResult], format: Format) -> String {
+    match format {
+        Format::Text => format_text(results),
+        Format::Json => format_json(results),
+        Format::Csv => format_csv(results),
+    }
+}
+
+fn format_text(results: &[PuzzleResult]) -> String {
+    let mut text = String::new();
+    for result in results {
+        let (day, part) = (result.day, result.part);
+        match &result.answer {
+            Answer::Text(answer) if answer.contains('\n') => {
+                writeln!(text, "Day {day}, challange {part}: \n{answer}").unwrap()
+            }
+            answer => writeln!(text, "Day {day}, challange {part}: {answer}").unwrap(),
+        }
+    }
+    text
+}
+
+fn json_string(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len() + 2);
+    escaped.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => write!(escaped, "\\u{:04x}", c as u32).unwrap(),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+fn format_json(results: &[PuzzleResult]) -> String {
+    let mut json = String::from("[");
+    for (i, result) in results.iter().enumerate() {
+        let answer = match &result.answer {
+            Answer::Integer(value) => value.to_string(),
+            Answer::Text(text) => json_string(text),
+        };
+        let separator = if i == 0 { "" } else { "," };
+        write!(
+            json,
+            "{separator}\n  {{\"day\": {}, \"part\": {}, \"answer\": {answer}, \"type\": \"{}\", \"duration_ns\": {}, \"day_duration_ns\": {}}}",
+            result.day,
+            result.part,
+            result.answer.kind(),
+            result.duration.as_nanos(),
+            result.day_duration.as_nanos()
+        )
+        .unwrap();
+    }
+    json.push_str("\n]\n");
+    json
+}
+
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+fn format_csv(results: &[PuzzleResult]) -> String {
+    let mut csv = String::from("day,part,answer,type,duration_ns,day_duration_ns\n");
+    for result in results {
+        writeln!(
+            csv,
+            "{},{},{},{},{},{}",
+            result.day,
+            result.part,
+            csv_field(&result.answer.to_string()),
+            result.answer.kind(),
+            result.duration.as_nanos(),
+            result.day_duration.as_nanos()
+        )
+        .unwrap();
+    }
+    csv
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn results() -> Vec<PuzzleResult> {
+        vec![
+            PuzzleResult {
+                day: 1,
+                part: 1,
+                answer: Answer::Integer(24000),
+                duration: Duration::from_nanos(1500),
+                day_duration: Duration::from_nanos(4000),
+            },
+            PuzzleResult {
+                day: 10,
+                part: 2,
+                answer: Answer::Text("#.\n\"#\"\n".to_string()),
+                duration: Duration::from_micros(2),
+                day_duration: Duration::from_micros(5),
+            },
+        ]
+    }
+
+    #[test]
+    fn format_text_example() {
+        assert_eq!(
+            format(&results(), Format::Text),
+            "Day 1, challange 1: 24000\nDay 10, challange 2: \n#.\n\"#\"\n\n"
+        );
+    }
+
+    #[test]
+    fn format_json_example() {
+        assert_eq!(
+            format(&results(), Format::Json),
+            r##"[
+  {"day": 1, "part": 1, "answer": 24000, "type": "integer", "duration_ns": 1500, "day_duration_ns": 4000},
+  {"day": 10, "part": 2, "answer": "#.\n\"#\"\n", "type": "text", "duration_ns": 2000, "day_duration_ns": 5000}
+]
+"##
+        );
+        assert_eq!(format(&[], Format::Json), "[\n]\n");
+    }
+
+    #[test]
+    fn format_csv_example() {
+        assert_eq!(
+            format(&results(), Format::Csv),
+            "day,part,answer,type,duration_ns,day_duration_ns\n1,1,24000,integer,1500,4000\n10,2,\"#.\n\"\"#\"\"\n\",text,2000,5000\n"
+        );
+    }
+
+    #[test]
+    fn format_from_str() {
+        assert_eq!("json".parse(), Ok(Format::Json));
+        assert!("xml".parse::<Format>().is_err());
+    }
+}
@@ -0,0 +1,263 @@
+/* Copyright (C) 2022 Casper Meijn <casper@meijn.net>
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use crate::solution::{Answer, DayError, DynSolution};
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+/// The answer to a single challange of a day.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PuzzleResult {
+    pub day: u8,
+    pub part: u8,
+    pub answer: Answer,
+    /// Time spent solving the challange, excluding parsing.
+    pub duration: Duration,
+    /// Time spent parsing the input of the day and solving all of its selected challanges.
+    pub day_duration: Duration,
+}
+
+/// Parses the input of a day once and solves the selected challanges.
+///
+/// Returns a result per part, so a challange without an answer doesn't hide the answers to the
+/// others. When the input fails to parse, the parse error is the only result.
+pub fn solve(
+    day: &dyn DynSolution,
+    text: &str,
+    parts: &[u8],
+) -> Vec<Result<PuzzleResult, DayError>> {
+    let day_start = Instant::now();
+    let input = match day.parse(text) {
+        Ok(input) => input,
+        Err(error) => return vec![Err(error.into())],
+    };
+    let mut results: Vec<_> = parts
+        .iter()
+        .map(|&part| {
+            let start = Instant::now();
+            let answer = match part {
+                1 => day.challange1(input.as_ref()),
+                2 => day.challange2(input.as_ref()),
+                _ => panic!("day {} has no challange {part}", day.day()),
+            };
+            let duration = start.elapsed();
+            answer
+                .map(|answer| PuzzleResult {
+                    day: day.day(),
+                    part,
+                    answer,
+                    duration,
+                    day_duration: Duration::ZERO,
+                })
+                .map_err(|error| DayError::Solve { part, error })
+        })
+        .collect();
+    let day_duration = day_start.elapsed();
+    for result in results.iter_mut().flatten() {
+        result.day_duration = day_duration;
+    }
+    results
+}
+
+/// The input of a day that is still to be solved.
+pub struct Job<'a> {
+    pub day: &'a dyn DynSolution,
+    pub text: String,
+}
+
+/// Outcome of a [`Job`], including the time spent on it.
+#[derive(Debug)]
+pub struct JobResult {
+    pub day: u8,
+    pub results: Vec<Result<PuzzleResult, DayError>>,
+    /// Wall-clock time spent parsing and solving on the worker thread. When there are more jobs
+    /// than cores, this includes time the thread had to wait for a core.
+    pub duration: Duration,
+    /// CPU time the worker thread spent parsing and solving, or `None` when the platform doesn't
+    /// provide a per-thread CPU clock.
+    pub cpu_time: Option<Duration>,
+}
+
+/// Returns the CPU time consumed by the current thread.
+#[cfg(unix)]
+fn thread_cpu_time() -> Option<Duration> {
+    let mut time = libc::timespec {
+        tv_sec: 0,
+        tv_nsec: 0,
+    };
+    // SAFETY: `time` is a valid, writable `timespec` for the duration of the call.
+    let status = unsafe { libc::clock_gettime(libc::CLOCK_THREAD_CPUTIME_ID, &mut time) };
+    if status != 0 {
+        return None;
+    }
+    Some(Duration::new(
+        u64::try_from(time.tv_sec).ok()?,
+        u32::try_from(time.tv_nsec).ok()?,
+    ))
+}
+
+#[cfg(not(unix))]
+fn thread_cpu_time() -> Option<Duration> {
+    None
+}
+
+fn run_job(job: &Job, parts: &[u8]) -> JobResult {
+    let start = Instant::now();
+    let cpu_start = thread_cpu_time();
+    let results = solve(job.day, &job.text, parts);
+    let cpu_time = thread_cpu_time()
+        .zip(cpu_start)
+        .map(|(end, start)| end - start);
+    JobResult {
+        day: job.day.day(),
+        results,
+        duration: start.elapsed(),
+        cpu_time,
+    }
+}
+
+/// Solves all jobs using up to `threads` worker threads.
+///
+/// Each day is parsed and solved on a single worker. The results are returned in the same order
+/// as the jobs, regardless of which worker finished first.
+pub fn solve_jobs(jobs: &[Job], parts: &[u8], threads: usize) -> Vec<JobResult> {
+    let threads = threads.clamp(1, jobs.len().max(1));
+    if threads == 1 {
+        return jobs.iter().map(|job| run_job(job, parts)).collect();
+    }
+
+    let next_job = AtomicUsize::new(0);
+    let results = Mutex::new((0..jobs.len()).map(|_| None).collect::<Vec<_>>());
+    thread::scope(|scope| {
+        for _ in 0..threads {
+            scope.spawn(|| loop {
+                let i = next_job.fetch_add(1, Ordering::Relaxed);
+                let Some(job) = jobs.get(i) else {
+                    break;
+                };
+                let result = run_job(job, parts);
+                results.lock().unwrap()[i] = Some(result);
+            });
+        }
+    });
+    results
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .map(|result| result.expect("every job is run by a worker"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn solve_example() {
+        let day = crate::day(6).unwrap();
+        let results = solve(day, "mjqjpqmgbljsphdztnvjfqwrcgsmlb", &[2]);
+        assert_eq!(results.len(), 1);
+        let result = results[0].as_ref().unwrap();
+        assert_eq!(result.day, 6);
+        assert_eq!(result.part, 2);
+        assert_eq!(result.answer, Answer::Integer(19));
+        assert!(result.day_duration >= result.duration);
+    }
+
+    #[test]
+    fn solve_errors() {
+        let day = crate::day(5).unwrap();
+        let results = solve(day, "[A]\n 1 \n\nmove 2 from 1 to 1\n", &[1, 2]);
+        assert!(matches!(
+            results[..],
+            [
+                Err(DayError::Solve { part: 1, .. }),
+                Err(DayError::Solve { part: 2, .. })
+            ]
+        ));
+        let results = solve(day, "[A]\n 1 \n", &[1, 2]);
+        assert!(matches!(results[..], [Err(DayError::Parse(_))]));
+
+        let day = crate::day(6).unwrap();
+        let results = solve(day, "abcabcabcd", &[1, 2]);
+        assert_eq!(results[0].as_ref().unwrap().answer, Answer::Integer(10));
+        assert!(matches!(results[1], Err(DayError::Solve { part: 2, .. })));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn run_job_cpu_time() {
+        let job = Job {
+            day: crate::day(6).unwrap(),
+            text: "mjqjpqmgbljsphdztnvjfqwrcgsmlb".repeat(1000),
+        };
+        let result = run_job(&job, &[1, 2]);
+        assert!(result.cpu_time.unwrap() > Duration::ZERO);
+    }
+
+    #[test]
+    fn solve_jobs_in_order() {
+        let texts = [
+            "mjqjpqmgbljsphdztnvjfqwrcgsmlb",
+            "bvwbjplbgvbhsrlpgdmjqwftvncz",
+            "nppdvjthqldpwncqszvftbrmjlhg",
+        ];
+        let jobs = [
+            Job {
+                day: crate::day(6).unwrap(),
+                text: texts[0].to_string(),
+            },
+            Job {
+                day: crate::day(4).unwrap(),
+                text: "2-4,6-8\n2-8,3-7".to_string(),
+            },
+            Job {
+                day: crate::day(6).unwrap(),
+                text: texts[2].to_string(),
+            },
+            Job {
+                day: crate::day(6).unwrap(),
+                text: texts[1].to_string(),
+            },
+        ];
+        let sequential = solve_jobs(&jobs, &[1, 2], 1);
+        let parallel = solve_jobs(&jobs, &[1, 2], 3);
+        let answers = |results: &[JobResult]| {
+            results
+                .iter()
+                .flat_map(|result| &result.results)
+                .map(|result| result.as_ref().unwrap())
+                .map(|result| (result.day, result.part, result.answer.clone()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(answers(&parallel), answers(&sequential));
+        assert_eq!(
+            parallel.iter().map(|result| result.day).collect::<Vec<_>>(),
+            vec![6, 4, 6, 6]
+        );
+        assert_eq!(
+            answers(&parallel)[2..4],
+            [(4, 1, Answer::Integer(1)), (4, 2, Answer::Integer(1))]
+        );
+    }
+}
@@ -0,0 +1,209 @@
+/* Copyright (C) 2022 Casper Meijn <casper@meijn.net>
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use crate::parse::ParseError;
+use std::{any::Any, error::Error, fmt};
+
+/// The answer to a single challange, either a number or a piece of text.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Answer {
+    Integer(i64),
+    Text(String),
+}
+
+impl Answer {
+    /// Name of the type of answer, as used in machine readable output.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Answer::Integer(_) => "integer",
+            Answer::Text(_) => "text",
+        }
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Integer(value) => write!(f, "{value}"),
+            Answer::Text(text) => write!(f, "{text}"),
+        }
+    }
+}
+
+impl From<i32> for Answer {
+    fn from(value: i32) -> Self {
+        Answer::Integer(value.into())
+    }
+}
+
+impl From<u32> for Answer {
+    fn from(value: u32) -> Self {
+        Answer::Integer(value.into())
+    }
+}
+
+impl From<usize> for Answer {
+    fn from(value: usize) -> Self {
+        Answer::Integer(value.try_into().unwrap())
+    }
+}
+
+impl From<String> for Answer {
+    fn from(text: String) -> Self {
+        Answer::Text(text)
+    }
+}
+
+/// Error returned when a challange has no answer for an input that was parsed successfully.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SolveError {
+    message: String,
+}
+
+impl SolveError {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl Error for SolveError {}
+
+/// Error returned when a day can't be parsed or one of its challanges can't be solved.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DayError {
+    Parse(ParseError),
+    Solve { part: u8, error: SolveError },
+}
+
+impl fmt::Display for DayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DayError::Parse(error) => write!(f, "failed to parse input: {error}"),
+            DayError::Solve { part, error } => write!(f, "challange {part} has no answer: {error}"),
+        }
+    }
+}
+
+impl Error for DayError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            DayError::Parse(error) => Some(error),
+            DayError::Solve { error, .. } => Some(error),
+        }
+    }
+}
+
+impl From<ParseError> for DayError {
+    fn from(error: ParseError) -> Self {
+        DayError::Parse(error)
+    }
+}
+
+/// A puzzle of a single day: parse the input once and solve both challanges on it.
+pub trait Solution {
+    const DAY: u8;
+
+    type Input;
+
+    fn parse(text: &str) -> Result<Self::Input, ParseError>;
+
+    fn challange1(input: &Self::Input) -> Result<Answer, SolveError>;
+
+    fn challange2(input: &Self::Input) -> Result<Answer, SolveError>;
+}
+
+/// Object safe version of [`Solution`], so days can be stored in the registry.
+pub trait DynSolution: Sync {
+    fn day(&self) -> u8;
+
+    fn parse(&self, text: &str) -> Result<Box<dyn Any>, ParseError>;
+
+    fn challange1(&self, input: &dyn Any) -> Result<Answer, SolveError>;
+
+    fn challange2(&self, input: &dyn Any) -> Result<Answer, SolveError>;
+}
+
+fn downcast<S: Solution>(input: &dyn Any) -> &S::Input
+where
+    S::Input: 'static,
+{
+    input
+        .downcast_ref()
+        .expect("input was parsed by a different day")
+}
+
+impl<S> DynSolution for S
+where
+    S: Solution + Sync,
+    S::Input: 'static,
+{
+    fn day(&self) -> u8 {
+        S::DAY
+    }
+
+    fn parse(&self, text: &str) -> Result<Box<dyn Any>, ParseError> {
+        Ok(Box::new(<S as Solution>::parse(text)?))
+    }
+
+    fn challange1(&self, input: &dyn Any) -> Result<Answer, SolveError> {
+        <S as Solution>::challange1(downcast::<S>(input))
+    }
+
+    fn challange2(&self, input: &dyn Any) -> Result<Answer, SolveError> {
+        <S as Solution>::challange2(downcast::<S>(input))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DAYS;
+
+    #[test]
+    fn registry_is_in_order() {
+        for (i, day) in DAYS.iter().enumerate() {
+            assert_eq!(usize::from(day.day()), i + 1);
+        }
+    }
+
+    #[test]
+    fn dyn_solution_example() {
+        let day = crate::day(6).unwrap();
+        let input = day.parse("mjqjpqmgbljsphdztnvjfqwrcgsmlb").unwrap();
+        assert_eq!(day.challange1(input.as_ref()), Ok(Answer::Integer(7)));
+        assert_eq!(day.challange2(input.as_ref()), Ok(Answer::Integer(19)));
+    }
+
+    #[test]
+    fn answer_display() {
+        assert_eq!(Answer::from(42_u32).to_string(), "42");
+        assert_eq!(Answer::from(-3_i32).to_string(), "-3");
+        assert_eq!(Answer::from("CMZ".to_string()).to_string(), "CMZ");
+    }
+}